@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::fs;
+use std::process;
+
+use algovis::config::maze_config::MazeConfigValues;
+use algovis::config::select_option::SelectOption;
+use algovis::config::sort_config::SortConfigValues;
+use algovis::services::maze_generator::{Cell, Maze};
+use algovis::services::mazer::Mazer;
+use algovis::services::playable::Playable;
+use algovis::services::any_sorter::AnySorter;
+use algovis::services::trace::{to_json, TraceFormat};
+
+const USAGE: &str = "Usage:
+  algovis-cli sort [--algorithm NAME] [--items N] [--distribution NAME] [--values KIND] [--input FILE]
+                   [--order NAME] [--key NAME] [--seed N] [--radix-base N] [--partition NAME] [--pivot NAME] [--trace FILE]
+  algovis-cli maze [--algorithm NAME] [--size N] [--input FILE] [--seed N] [--trace FILE]
+
+Sort input files hold values of the chosen kind separated by commas or whitespace: whole
+numbers, decimal numbers (floats), words, or key:name records. Keys other than value
+need whole numbers or records. Maze input files
+draw the maze with # for walls, . for empty cells, S for the entry and E for the exit.
+Traces are written as JSON, or as CSV when the file name ends in .csv.";
+
+enum Command {
+    Sort,
+    Maze,
+}
+
+impl Command {
+    fn from_string(s: &str) -> Result<Command, &'static str> {
+        match s {
+            "sort" => Ok(Command::Sort),
+            "maze" => Ok(Command::Maze),
+            _ => Err("Invalid command"),
+        }
+    }
+}
+
+/// `--name value` pairs following the command
+fn parse_options(args: &[String], known: &[&str]) -> Result<HashMap<String, String>, String> {
+    let mut options = HashMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let name = arg
+            .strip_prefix("--")
+            .filter(|name| known.contains(name))
+            .ok_or_else(|| format!("Unknown option \"{}\"", arg))?;
+        let value = args.next().ok_or_else(|| format!("Option --{} needs a value", name))?;
+        options.insert(name.to_string(), value.clone());
+    }
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("--{} expects a whole number, got \"{}\"", name, value))
+}
+
+fn choose(name: &str, value: &str, options: &[SelectOption]) -> Result<String, String> {
+    if options.iter().any(|option| option.value == value) {
+        return Ok(value.to_string());
+    }
+    let values = options.iter().map(|option| option.value.as_str()).collect::<Vec<_>>();
+    Err(format!("Unknown --{} \"{}\", expected one of: {}", name, value, values.join(", ")))
+}
+
+fn read_file(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("Cannot read {}: {}", path, err))
+}
+
+fn write_trace(path: &str, json: String, csv: String) -> Result<(), String> {
+    let contents = if path.ends_with(".csv") { csv } else { json };
+    fs::write(path, contents).map_err(|err| format!("Cannot write {}: {}", path, err))
+}
+
+fn run_sort(args: &[String]) -> Result<(), String> {
+    let options = parse_options(
+        args,
+        &["algorithm", "items", "distribution", "values", "input", "order", "key", "seed", "radix-base", "partition", "pivot", "trace"],
+    )?;
+    let mut config = SortConfigValues::new();
+    for (name, value) in &options {
+        match name.as_str() {
+            "algorithm" => config.current_algorithm_name = choose(name, value, &config.alg_options)?,
+            "items" => config.items_count = parse_number(name, value)?,
+            "distribution" => config.distribution = choose(name, value, &config.distribution_options)?,
+            "values" => config.item_kind = choose(name, value, &config.item_kind_options)?,
+            "input" => {
+                config.custom_input = read_file(value)?;
+                config.input_mode = String::from("custom");
+            }
+            "order" => config.sort_order = choose(name, value, &config.sort_order_options)?,
+            "key" => config.key_mode = choose(name, value, &config.key_mode_options)?,
+            "seed" => config.seed = parse_number(name, value)?,
+            "radix-base" => config.radix_base = choose(name, value, &config.radix_options)?.parse().unwrap(),
+            "partition" => config.partition_scheme = choose(name, value, &config.partition_options)?,
+            "pivot" => config.pivot_choice = choose(name, value, &config.pivot_options)?,
+            _ => (),
+        }
+    }
+    if config.items_count < 1 {
+        return Err(String::from("--items must be at least 1"));
+    }
+    // Checked once every option is in, it depends on --values
+    if let Some(path) = options.get("input") {
+        config.item_kind().check_custom_input(&config.custom_input).map_err(|err| format!("{}: {}", path, err))?;
+    }
+    if !config.algorithm_options().iter().any(|option| option.value == config.current_algorithm_name) {
+        return Err(format!("--algorithm {} cannot sort {}", config.current_algorithm_name, config.item_kind));
+    }
+    if config.key_mode != "value" && !config.item_kind().has_int_key() {
+        return Err(format!("--key {} cannot compare {}", config.key_mode, config.item_kind));
+    }
+
+    let mut sorter = AnySorter::new(&config);
+    if let Some(fallback) = sorter.fallback() {
+        eprintln!("note: {}", fallback);
+    }
+    let stats = sorter.stats();
+    let initial_len = sorter.magnitudes().len();
+    sorter.set_step(sorter.get_final_step());
+    let is_sorted = sorter.is_sorted();
+
+    println!("algorithm: {}", config.algorithm_label(&config.current_algorithm_name));
+    println!("values: {}", config.item_kind);
+    println!("order: {} by {}", config.sort_order, config.key_mode);
+    println!("items: {}", initial_len);
+    println!("seed: {}", config.seed);
+    println!("steps: {}", sorter.get_final_step());
+    println!("comparisons: {}", stats.comparisons);
+    println!("swaps: {}", stats.swaps);
+    println!("writes: {}", stats.writes);
+    println!("peak aux memory: {}", stats.peak_aux_memory);
+    println!("sorted: {}", is_sorted);
+
+    if let Some(path) = options.get("trace") {
+        let json = sorter.export_trace(&config, TraceFormat::Json);
+        write_trace(path, json, sorter.export_trace(&config, TraceFormat::Csv))?;
+    }
+    if is_sorted {
+        Ok(())
+    } else {
+        Err(String::from("The trace does not end sorted"))
+    }
+}
+
+fn run_maze(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, &["algorithm", "size", "input", "seed", "trace"])?;
+    let mut config = MazeConfigValues::new();
+    for (name, value) in &options {
+        match name.as_str() {
+            "algorithm" => config.current_algorithm_name = choose(name, value, &config.alg_options)?,
+            "size" => config.size = parse_number(name, value)?,
+            "seed" => config.seed = parse_number(name, value)?,
+            _ => (),
+        }
+    }
+    // Generated mazes any smaller leave the exit walled off
+    if config.size < 6 {
+        return Err(String::from("--size must be at least 6"));
+    }
+
+    let mut mazer = Mazer::new(&config);
+    if let Some(path) = options.get("input") {
+        let maze = Maze::from_text(&read_file(path)?).map_err(|err| format!("{}: {}", path, err))?;
+        mazer.width = maze.width;
+        mazer.height = maze.height;
+        mazer.maze = maze;
+    }
+    mazer.solve(&config);
+    let visited = mazer.steps.iter().filter(|step| step.cell_type == Cell::Visited).count();
+
+    let label = config.alg_options.iter().find(|option| option.value == config.current_algorithm_name);
+    println!("algorithm: {}", label.map_or(config.current_algorithm_name.as_str(), |option| option.label.as_str()));
+    println!("size: {}x{}", mazer.width, mazer.height);
+    if !options.contains_key("input") {
+        println!("seed: {}", config.seed);
+    }
+    println!("steps: {}", mazer.steps.len());
+    println!("visited cells: {}", visited);
+    println!("path length: {}", mazer.path.len());
+
+    if let Some(path) = options.get("trace") {
+        let trace = mazer.to_trace(&config);
+        write_trace(path, to_json(&trace), trace.to_csv())?;
+    }
+    if mazer.path.is_empty() {
+        Err(String::from("The exit cannot be reached"))
+    } else {
+        Ok(())
+    }
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let Some(command) = args.first() else {
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    };
+    if command == "--help" || command == "-h" {
+        println!("{}", USAGE);
+        return;
+    }
+    let result = match Command::from_string(command) {
+        Ok(Command::Sort) => run_sort(&args[1..]),
+        Ok(Command::Maze) => run_maze(&args[1..]),
+        Err(_) => {
+            eprintln!("Unknown command \"{}\"\n\n{}", command, USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(message) = result {
+        eprintln!("error: {}", message);
+        process::exit(1);
+    }
+}
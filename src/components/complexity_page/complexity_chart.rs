@@ -0,0 +1,162 @@
+use wasm_bindgen::prelude::*;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use yew::prelude::*;
+
+use algovis::services::complexity::{Metric, Reference, Series, SWEEP_SIZES};
+
+const MARGIN_LEFT: f64 = 60.;
+const MARGIN_BOTTOM: f64 = 36.;
+const MARGIN_TOP: f64 = 10.;
+const MARGIN_RIGHT: f64 = 60.;
+const AXIS_COLOR: &str = "#8d99ae";
+const REFERENCE_COLOR: &str = "#adb5bd";
+const POINT_RADIUS: f64 = 3.;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub series: Vec<Series>,
+    pub metric: Metric,
+    #[prop_or(950)]
+    pub width: u32,
+    #[prop_or(500)]
+    pub height: u32,
+}
+
+/// Color of the series at `idx`, shared with the legend
+pub fn series_color(idx: usize, count: usize) -> String {
+    format!("hsl({}, 70%, 55%)", idx as f64 / count.max(1) as f64 * 300.)
+}
+
+/// Maps `log(n)` and `log10(y)` into the plot area
+struct Axes {
+    x_range: (f64, f64),
+    y_range: (f64, f64),
+    width: f64,
+    height: f64,
+}
+
+impl Axes {
+    fn x(&self, n: f64) -> f64 {
+        let (lo, hi) = self.x_range;
+        MARGIN_LEFT + (n.log2() - lo) / (hi - lo) * (self.width - MARGIN_LEFT - MARGIN_RIGHT)
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        let (lo, hi) = self.y_range;
+        let plot_height = self.height - MARGIN_TOP - MARGIN_BOTTOM;
+        MARGIN_TOP + plot_height - (value.log10() - lo) / (hi - lo) * plot_height
+    }
+}
+
+/// Step counts against n on log-log axes, with reference curves fitted to all points
+#[function_component(ComplexityChart)]
+pub fn complexity_chart(props: &Props) -> Html {
+    let canvas_ref = use_node_ref();
+    {
+        let canvas_ref = canvas_ref.clone();
+        let points = props.series.iter().map(|series| series.points(props.metric)).collect::<Vec<_>>();
+        use_effect(move || {
+            let canvas: HtmlCanvasElement = canvas_ref.cast::<HtmlCanvasElement>().unwrap();
+            let context = canvas
+                .get_context("2d")
+                .unwrap()
+                .unwrap()
+                .dyn_into::<CanvasRenderingContext2d>()
+                .unwrap();
+            let str_to_js = |str: &str| JsValue::from(str);
+            context.clear_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+
+            let all_points = points.iter().flatten().copied().collect::<Vec<(usize, u32)>>();
+            let max_value = all_points.iter().map(|&(_, y)| y).max().unwrap_or(1).max(1) as f64;
+            let axes = Axes {
+                x_range: ((SWEEP_SIZES[0] as f64).log2(), (SWEEP_SIZES[SWEEP_SIZES.len() - 1] as f64).log2()),
+                y_range: (0., max_value.log10().ceil().max(1.)),
+                width: canvas.width() as f64,
+                height: canvas.height() as f64,
+            };
+            let bottom = axes.height - MARGIN_BOTTOM;
+
+            context.set_stroke_style(&str_to_js(AXIS_COLOR));
+            context.set_fill_style(&str_to_js(AXIS_COLOR));
+            context.set_line_width(1.);
+            context.set_font("12px sans-serif");
+            context.set_text_align("center");
+            for n in SWEEP_SIZES {
+                let x = axes.x(n as f64);
+                context.begin_path();
+                context.move_to(x, MARGIN_TOP);
+                context.line_to(x, bottom);
+                context.stroke();
+                context.fill_text(&n.to_string(), x, bottom + 16.).unwrap();
+            }
+            context.fill_text("n", axes.width / 2., bottom + 32.).unwrap();
+            context.set_text_align("right");
+            for power in 0..=axes.y_range.1 as i32 {
+                let y = axes.y(10f64.powi(power));
+                context.begin_path();
+                context.move_to(MARGIN_LEFT, y);
+                context.line_to(axes.width - MARGIN_RIGHT, y);
+                context.stroke();
+                context.fill_text(&format!("1e{}", power), MARGIN_LEFT - 6., y + 4.).unwrap();
+            }
+
+            // Reference curves are dashed and labelled where they leave the plot
+            context.set_stroke_style(&str_to_js(REFERENCE_COLOR));
+            context.set_fill_style(&str_to_js(REFERENCE_COLOR));
+            context.set_text_align("left");
+            context.set_line_dash(&js_sys::Array::of2(&6.into(), &4.into())).unwrap();
+            for reference in Reference::ALL {
+                let Some(factor) = reference.fit(&all_points) else {
+                    continue;
+                };
+                context.begin_path();
+                for (idx, n) in SWEEP_SIZES.iter().enumerate() {
+                    let n = *n as f64;
+                    let (x, y) = (axes.x(n), axes.y(factor * reference.value(n)).max(MARGIN_TOP));
+                    if idx == 0 {
+                        context.move_to(x, y);
+                    } else {
+                        context.line_to(x, y);
+                    }
+                }
+                context.stroke();
+                let last = SWEEP_SIZES[SWEEP_SIZES.len() - 1] as f64;
+                let label_y = axes.y(factor * reference.value(last)).clamp(MARGIN_TOP + 8., bottom);
+                context.fill_text(reference.label(), axes.width - MARGIN_RIGHT + 6., label_y).unwrap();
+            }
+            context.set_line_dash(&js_sys::Array::new()).unwrap();
+
+            context.set_line_width(2.);
+            for (idx, series_points) in points.iter().enumerate() {
+                let color = str_to_js(&series_color(idx, points.len()));
+                context.set_stroke_style(&color);
+                context.set_fill_style(&color);
+                // Zero counts have no place on a log axis and break the line
+                let mut is_drawing = false;
+                context.begin_path();
+                for &(n, value) in series_points {
+                    let (x, y) = (axes.x(n as f64), axes.y(value as f64));
+                    if value == 0 {
+                        is_drawing = false;
+                    } else if is_drawing {
+                        context.line_to(x, y);
+                    } else {
+                        context.move_to(x, y);
+                        is_drawing = true;
+                    }
+                }
+                context.stroke();
+                for &(n, value) in series_points.iter().filter(|&&(_, value)| value > 0) {
+                    context.begin_path();
+                    context
+                        .arc(axes.x(n as f64), axes.y(value as f64), POINT_RADIUS, 0., std::f64::consts::TAU)
+                        .unwrap();
+                    context.fill();
+                }
+            }
+        });
+    }
+    html! {
+        <canvas ref={canvas_ref} class="w-full block" width={props.width.to_string()} height={props.height.to_string()} />
+    }
+}
@@ -0,0 +1 @@
+pub mod complexity_chart;
@@ -1,37 +1,12 @@
 use yew::prelude::*;
 
+use algovis::config::maze_config::MazeConfigValues;
+use algovis::helpers::{parse_string_to_i32_or_default, parse_string_to_u64_or_default, random_seed};
+use algovis::services::maze_generator::Cell;
+
 use crate::components::ui::the_button::TheButton;
 use crate::components::ui::the_input::TheInput;
-use crate::components::ui::the_select::{SelectOption, TheSelect};
-use crate::helpers::parse_string_to_i32_or_default;
-use crate::services::maze_generator::Cell;
-
-#[derive(Clone, PartialEq)]
-pub struct MazeConfigValues {
-    pub time_overall: i32,
-    pub current_algorithm_name: String,
-    pub size: usize,
-    pub cell_type: Cell,
-    pub current_step: u32,
-    alg_options: Vec<SelectOption>,
-}
-
-impl MazeConfigValues {
-    pub fn new() -> Self {
-        let default_algorithm = "dfs".to_string();
-        Self {
-            time_overall: 2,
-            size: 35,
-            cell_type: Cell::Entry,
-            current_algorithm_name: default_algorithm.clone(),
-            alg_options: vec![
-                SelectOption { value: default_algorithm, label: String::from("DFS"), },
-                SelectOption { value: "dijkstra".to_string(), label: String::from("Dijkstra"), },
-            ],
-            current_step: 0,
-        }
-    }
-}
+use crate::components::ui::the_select::TheSelect;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
@@ -43,7 +18,20 @@ pub struct Props {
 
 #[function_component(MazeConfig)]
 pub fn maze_config(props: &Props) -> Html {
-    let config = use_state(|| MazeConfigValues::new());
+    let config = use_state(|| props.value.clone());
+
+    // Values replaced from outside, an imported trace for one, show up in the form
+    {
+        let config = config.clone();
+        use_effect_with_deps(
+            move |value| {
+                if *value != *config {
+                    config.set(value.clone());
+                }
+            },
+            props.value.clone(),
+        );
+    }
 
     // todo: somehow manage to update values via single function?
     let change_size = {
@@ -56,7 +44,6 @@ pub fn maze_config(props: &Props) -> Html {
         })
     };
 
-
     let change_time_overall = {
         let config = config.clone();
         Callback::from(move |value: String| {
@@ -76,6 +63,24 @@ pub fn maze_config(props: &Props) -> Html {
         })
     };
 
+    let change_seed = {
+        let config = config.clone();
+        Callback::from(move |value: String| {
+            let mut config_value = (*config).clone();
+            config_value.seed = parse_string_to_u64_or_default(value, 0);
+            config.set(config_value);
+        })
+    };
+
+    let new_seed = {
+        let config = config.clone();
+        Callback::from(move |_| {
+            let mut config_value = (*config).clone();
+            config_value.seed = random_seed();
+            config.set(config_value);
+        })
+    };
+
     {
         let on_change = props.on_change.clone();
         let config = config.clone();
@@ -115,9 +120,17 @@ pub fn maze_config(props: &Props) -> Html {
                     value={config.time_overall.to_string()}
                     set_value={change_time_overall}
                 />
+                <TheInput
+                    label="Seed"
+                    value={config.seed.to_string()}
+                    set_value={change_seed}
+                />
+                <TheButton class="mt-2" onclick={new_seed}>
+                    {"New seed"}
+                </TheButton>
                 <TheSelect
                     label="Maze Walker Algorithm"
-                    value={(*config).current_algorithm_name.clone()}
+                    value={config.current_algorithm_name.clone()}
                     on_change={change_current_algorithm}
                     options={config.alg_options.clone()}
                 />
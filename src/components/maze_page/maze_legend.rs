@@ -1,6 +1,7 @@
-use crate::services::maze_generator::Cell;
 use yew::prelude::*;
 
+use algovis::services::maze_generator::Cell;
+
 #[function_component(MazeLegend)]
 pub fn maze_legend() -> Html {
     html! {
@@ -2,7 +2,7 @@ use wasm_bindgen::prelude::*;
 use web_sys::{CanvasRenderingContext2d, Element, HtmlCanvasElement};
 use yew::prelude::*;
 
-use crate::services::{maze_generator::Cell, mazer::Mazer};
+use algovis::services::{maze_generator::{Cell, Coords}, mazer::Mazer};
 
 const BLACK: &str = "#000000";
 const ROUND_STYLE: &str = "round";
@@ -13,22 +13,6 @@ pub struct Props {
     pub on_cell_click: Callback<MazeItem>,
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
-pub struct Coords<T: Copy> {
-    pub x: T,
-    pub y: T,
-}
-
-impl<T: Copy> Coords<T> {
-    pub fn from(x: T, y: T) -> Self {
-        Coords::<T> { x, y }
-    }
-
-    pub fn as_row_col(&self) -> (T, T) {
-        (self.y, self.x)
-    }
-}
-
 #[derive(Clone, Debug)]
 pub struct MazeItem {
     x: f64,
@@ -99,7 +83,7 @@ pub fn find_item_by_coords(point: Coords<i32>, maze_cells: &Vec<MazeItem>) -> Op
 pub fn create_path_line(
     context: &CanvasRenderingContext2d,
     mazer: &Mazer,
-    maze_cells: &Vec<MazeItem>,
+    maze_cells: &[MazeItem],
 ) {
     if mazer.path.len() <= 1 {
         return;
@@ -145,7 +129,7 @@ pub fn draw_maze(
     context.set_fill_style(&str_to_js(BLACK));
     for x_idx in 0..mazer.width {
         for y_idx in 0..mazer.height {
-            let mut maze_item = calculate_item(x_idx, y_idx, mazer.width, mazer.height, &canvas);
+            let mut maze_item = calculate_item(x_idx, y_idx, mazer.width, mazer.height, canvas);
             let MazeItem {
                 x,
                 y,
@@ -154,7 +138,7 @@ pub fn draw_maze(
                 ..
             } = maze_item;
 
-            let (row, col) = (y_idx as usize, x_idx as usize);
+            let (row, col) = (y_idx, x_idx);
             let cell = mazer.maze.cells[row][col];
             context.set_fill_style(&str_to_js(cell.as_color()));
             context.fill_rect(x, y, width, height);
@@ -210,7 +194,7 @@ pub fn get_coords_by_mouse_event(e: MouseEvent) -> Coords<i32> {
 
 #[function_component(MazeViewCanvas)]
 pub fn maze_view_canvas(props: &Props) -> Html {
-    let maze_items: UseStateHandle<Vec<MazeItem>> = use_state(|| vec![]);
+    let maze_items: UseStateHandle<Vec<MazeItem>> = use_state(Vec::new);
     let mazer = props.mazer.clone();
     {
         let maze_items = maze_items.clone();
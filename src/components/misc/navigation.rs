@@ -22,6 +22,7 @@ pub fn navigation() -> Html {
                     <div class="flex gap-4 font-medium">
                         <a class="av-link" onclick={handle_route.clone().reform(|_| &Route::Sort)}>{ "Sorting" }</a>
                         <a class="av-link" onclick={handle_route.clone().reform(|_| &Route::Maze)}>{ "Maze Runner" }</a>
+                        <a class="av-link" onclick={handle_route.clone().reform(|_| &Route::Complexity)}>{ "Complexity" }</a>
                         <a class="av-link" onclick={handle_route.clone().reform(|_| &Route::Home)}>{ "About" }</a>
                         <a class="av-link" href="https://github.com/Vterebenin/algvis">{ "Github" }</a>
                     </div>
@@ -3,3 +3,4 @@ pub mod icons;
 pub mod misc;
 pub mod sorting_page;
 pub mod maze_page;
+pub mod complexity_page;
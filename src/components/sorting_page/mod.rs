@@ -1,2 +1,4 @@
 pub mod sorting_config;
 pub mod sorting_graph_canvas;
+pub mod sorting_race;
+pub mod synth;
@@ -1,40 +1,11 @@
 use yew::prelude::*;
 
-use crate::components::ui::the_input::TheInput;
-use crate::components::ui::the_select::{TheSelect, SelectOption};
-use crate::helpers::parse_string_to_i32_or_default;
-
-const MAX_ITEMS: i32 = 100;
-
-#[derive(Clone, PartialEq)]
-pub struct SortConfigValues {
-    pub items_count: i32,
-    pub time_overall: i32,
-    pub current_algorithm_name: String,
-    alg_options: Vec<SelectOption>,
-    pub current_step: u32,
-}
+use algovis::config::sort_config::SortConfigValues;
+use algovis::helpers::{parse_string_to_i32_or_default, parse_string_to_u64_or_default, random_seed};
 
-impl SortConfigValues {
-    pub fn new() -> Self {
-        let default_algorithm = "merge_sort".to_string();
-        Self {
-            items_count: MAX_ITEMS,
-            time_overall: 10,
-            current_algorithm_name: default_algorithm.clone(),
-            alg_options: vec![
-                SelectOption { value: default_algorithm, label: String::from("Merge Sort") },
-                SelectOption { value: String::from("bubble_sort"), label: String::from("Bubble Sort") },
-                SelectOption { value: String::from("heap_sort"), label: String::from("Heap Sort") },
-                SelectOption { value: String::from("quick_sort"), label: String::from("Quick Sort") },
-                SelectOption { value: String::from("insertion_sort"), label: String::from("Insertion Sort") },
-                SelectOption { value: String::from("shell_sort"), label: String::from("Shell Sort") },
-                // SelectOption { value: String::from("bucket_sort"), label: String::from("Bucket Sort") }
-            ],
-            current_step: 0,
-        }
-    }
-}
+use crate::components::ui::the_button::TheButton;
+use crate::components::ui::the_input::TheInput;
+use crate::components::ui::the_select::TheSelect;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
@@ -46,7 +17,20 @@ pub struct Props {
 
 #[function_component(SortingConfig)]
 pub fn sorting_config(props: &Props) -> Html {
-    let config = use_state(|| SortConfigValues::new());
+    let config = use_state(|| props.value.clone());
+
+    // Values replaced from outside, an imported trace for one, show up in the form
+    {
+        let config = config.clone();
+        use_effect_with_deps(
+            move |value| {
+                if *value != *config {
+                    config.set(value.clone());
+                }
+            },
+            props.value.clone(),
+        );
+    }
     let change_items_count = {
         let config = config.clone();
         Callback::from(move |value: String| {
@@ -75,6 +59,120 @@ pub fn sorting_config(props: &Props) -> Html {
             config.set(config_value);
         })
     };
+
+    let change_input_mode = {
+        let config = config.clone();
+        Callback::from(move |value: String| {
+            let mut config_value = (*config).clone();
+            config_value.input_mode = value;
+            config.set(config_value);
+        })
+    };
+
+    let change_item_kind = {
+        let config = config.clone();
+        Callback::from(move |value: String| {
+            let mut config_value = (*config).clone();
+            config_value.item_kind = value;
+            // Integer based sorts drop out for items without an integer key
+            let options = config_value.algorithm_options();
+            if !options.iter().any(|option| option.value == config_value.current_algorithm_name) {
+                config_value.current_algorithm_name = options[0].value.clone();
+            }
+            config.set(config_value);
+        })
+    };
+
+    let change_sort_order = {
+        let config = config.clone();
+        Callback::from(move |value: String| {
+            let mut config_value = (*config).clone();
+            config_value.sort_order = value;
+            config.set(config_value);
+        })
+    };
+
+    let change_key_mode = {
+        let config = config.clone();
+        Callback::from(move |value: String| {
+            let mut config_value = (*config).clone();
+            config_value.key_mode = value;
+            config.set(config_value);
+        })
+    };
+
+    let change_renderer = {
+        let config = config.clone();
+        Callback::from(move |value: String| {
+            let mut config_value = (*config).clone();
+            config_value.renderer = value;
+            config.set(config_value);
+        })
+    };
+
+    let change_custom_input = {
+        let config = config.clone();
+        Callback::from(move |value: String| {
+            let mut config_value = (*config).clone();
+            config_value.custom_input = value;
+            config.set(config_value);
+        })
+    };
+
+    let change_distribution = {
+        let config = config.clone();
+        Callback::from(move |value: String| {
+            let mut config_value = (*config).clone();
+            config_value.distribution = value;
+            config.set(config_value);
+        })
+    };
+
+    let change_radix_base = {
+        let config = config.clone();
+        Callback::from(move |value: String| {
+            let mut config_value = (*config).clone();
+            config_value.radix_base = parse_string_to_i32_or_default(value, 10) as u32;
+            config.set(config_value);
+        })
+    };
+
+    let change_partition_scheme = {
+        let config = config.clone();
+        Callback::from(move |value: String| {
+            let mut config_value = (*config).clone();
+            config_value.partition_scheme = value;
+            config.set(config_value);
+        })
+    };
+
+    let change_pivot_choice = {
+        let config = config.clone();
+        Callback::from(move |value: String| {
+            let mut config_value = (*config).clone();
+            config_value.pivot_choice = value;
+            config.set(config_value);
+        })
+    };
+
+    let change_seed = {
+        let config = config.clone();
+        Callback::from(move |value: String| {
+            let mut config_value = (*config).clone();
+            config_value.seed = parse_string_to_u64_or_default(value, 0);
+            config.set(config_value);
+        })
+    };
+
+    let new_seed = {
+        let config = config.clone();
+        Callback::from(move |_| {
+            let mut config_value = (*config).clone();
+            config_value.seed = random_seed();
+            config.set(config_value);
+        })
+    };
+
     {
         let on_change = props.on_change.clone();
         let config = config.clone();
@@ -84,24 +182,149 @@ pub fn sorting_config(props: &Props) -> Html {
         }, config_value);
     }
 
-    html! {
-        <div>
+    let input_fields = if config.is_custom_input() {
+        let error = match config.item_kind().check_custom_input(&config.custom_input) {
+            Ok(_) => html! {},
+            Err(message) => html! { <div class="text-sm text-red-500">{message}</div> },
+        };
+        let warning = match config.key_span_warning() {
+            Some(message) => html! { <div class="text-sm text-amber-500">{message}</div> },
+            None => html! {},
+        };
+        html! {
+            <>
+                <TheInput
+                    label="Values (comma or space separated)"
+                    value={config.custom_input.clone()}
+                    set_value={change_custom_input}
+                />
+                {error}
+                {warning}
+            </>
+        }
+    } else if config.is_stability_demo() {
+        html! {
             <TheInput
                 label="Items Count"
                 value={config.items_count.to_string()}
                 set_value={change_items_count}
             />
+        }
+    } else {
+        html! {
+            <>
+                <TheInput
+                    label="Items Count"
+                    value={config.items_count.to_string()}
+                    set_value={change_items_count}
+                />
+                <TheSelect
+                    label="Input Distribution"
+                    value={config.distribution.clone()}
+                    on_change={change_distribution}
+                    options={config.distribution_options.clone()}
+                />
+            </>
+        }
+    };
+
+    html! {
+        <div>
+            <TheSelect
+                label="Input"
+                value={config.input_mode.clone()}
+                on_change={change_input_mode}
+                options={config.input_mode_options.clone()}
+            />
+            <TheSelect
+                label="Value type"
+                value={config.item_kind.clone()}
+                on_change={change_item_kind}
+                options={config.item_kind_options.clone()}
+            />
+            {input_fields}
+            <TheSelect
+                label="Order"
+                value={config.sort_order.clone()}
+                on_change={change_sort_order}
+                options={config.sort_order_options.clone()}
+            />
+            {
+                // Words and decimals are only ever compared by value
+                if config.item_kind().has_int_key() {
+                    html! {
+                        <TheSelect
+                            label="Sort by"
+                            value={config.key_mode.clone()}
+                            on_change={change_key_mode}
+                            options={config.key_mode_options.clone()}
+                        />
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            <TheSelect
+                label="View"
+                value={config.renderer.clone()}
+                on_change={change_renderer}
+                options={config.renderer_options.clone()}
+            />
             <TheInput
                 label="Time to run (seconds)"
                 value={config.time_overall.to_string()}
                 set_value={change_time_overall}
             />
+            <TheInput
+                label="Seed"
+                value={config.seed.to_string()}
+                set_value={change_seed}
+            />
+            <TheButton class="mt-2" onclick={new_seed}>
+                {"New seed"}
+            </TheButton>
             <TheSelect 
                 label="Sorting Algorithm"
-                value={(*config).current_algorithm_name.clone()}
+                value={config.current_algorithm_name.clone()}
                 on_change={change_current_algorithm}
-                options={config.alg_options.clone()}
+                options={config.algorithm_options()}
             />
+            {
+                if config.current_algorithm_name == "quick_sort" {
+                    html! {
+                        <>
+                            <TheSelect
+                                label="Partition Scheme"
+                                value={config.partition_scheme.clone()}
+                                on_change={change_partition_scheme}
+                                options={config.partition_options.clone()}
+                            />
+                            <TheSelect
+                                label="Pivot"
+                                value={config.pivot_choice.clone()}
+                                on_change={change_pivot_choice}
+                                options={config.pivot_options.clone()}
+                            />
+                        </>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            {
+                if config.current_algorithm_name.starts_with("radix_sort") {
+                    html! {
+                        <TheSelect
+                            label="Radix Base"
+                            value={config.radix_base.to_string()}
+                            on_change={change_radix_base}
+                            options={config.radix_options.clone()}
+                        />
+                    }
+                } else {
+                    html! {}
+                }
+            }
         </div>
     }
 }
@@ -3,12 +3,86 @@ use wasm_bindgen::prelude::*;
 use web_sys::{HtmlCanvasElement, CanvasRenderingContext2d};
 use yew::prelude::*;
 
-use crate::services::sorter::SortType;
+use algovis::services::sorter::{Annotations, SortType};
+
+const BAR_COLOR: &str = "#ff5733";
+const SWAP_COLOR: &str = "#53c2da";
+const SET_COLOR: &str = "#9b5de5";
+const COMPARE_COLOR: &str = "#f5d547";
+const AUX_COLOR: &str = "#8d99ae";
+const AUX_CHART_SHARE: f64 = 0.25;
+const AUX_CHART_GAP: f64 = 10.;
+const RUN_COLORS: [&str; 2] = ["#06d6a0", "#118ab2"];
+const RUN_MARK_HEIGHT: f64 = 6.;
+const UNSTABLE_COLOR: &str = "#ef233c";
+const LABEL_MAX_ITEMS: usize = 30;
+const LABEL_COLOR: &str = "#8d99ae";
+const LABEL_PADDING: f64 = 4.;
+const BUCKET_COLORS: [&str; 6] = ["#2ec4b6", "#3a86ff", "#8ac926", "#ff006e", "#fb5607", "#6a4c93"];
+const PIVOT_COLOR: &str = "#38b000";
+const FOCUS_SHADE: &str = "rgba(83, 194, 218, 0.15)";
+const SORTED_SHADE: &str = "rgba(6, 214, 160, 0.2)";
+const HEAP_BOUNDARY_COLOR: &str = "#ffb703";
+const DOT_RADIUS: f64 = 3.;
+const STRIP_MARK_HEIGHT: f64 = 12.;
+
+/// How the items are drawn, every view shows the same data and active step
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Renderer {
+    Bars,
+    /// Index against value
+    Scatter,
+    /// One column per item, hued by value
+    ColorStrip,
+    /// Items around a circle, pulled towards the center the farther they are from their final place
+    DisparityCircle,
+    /// Index as the angle and value as the distance from the center
+    Spiral,
+}
+
+impl Renderer {
+    pub fn from_string(s: &str) -> Result<Renderer, &'static str> {
+        match s {
+            "bars" => Ok(Renderer::Bars),
+            "scatter" => Ok(Renderer::Scatter),
+            "color_strip" => Ok(Renderer::ColorStrip),
+            "disparity_circle" => Ok(Renderer::DisparityCircle),
+            "spiral" => Ok(Renderer::Spiral),
+            _ => Err("Invalid variant"),
+        }
+    }
+}
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
-    pub data: Vec<i32>,
-    pub active_step_item: SortType<i32>
+    /// Bar heights, see `SortItem::magnitude`
+    pub data: Vec<f64>,
+    pub value_range: (f64, f64),
+    /// Items written out, shown along the bars when there are few of them
+    #[prop_or_default]
+    pub labels: Vec<String>,
+    #[prop_or_default]
+    pub aux: Vec<usize>,
+    #[prop_or_default]
+    pub runs: Vec<(usize, usize)>,
+    /// Original positions of the items, colors the bars by them when present
+    #[prop_or_default]
+    pub tags: Vec<usize>,
+    #[prop_or_default]
+    pub unstable_pairs: Vec<(usize, usize)>,
+    pub active_step_item: SortType<f64>,
+    #[prop_or(Renderer::Bars)]
+    pub renderer: Renderer,
+    /// Pivot, working range, heap and finished region marked so far
+    #[prop_or_default]
+    pub annotations: Annotations,
+    /// How far each item is from its final position, for the disparity circle
+    #[prop_or_default]
+    pub displacements: Vec<usize>,
+    #[prop_or(950)]
+    pub width: u32,
+    #[prop_or(500)]
+    pub height: u32,
 }
 
 pub struct ChartItem {
@@ -18,14 +92,11 @@ pub struct ChartItem {
     height: f64,
 }
 
-pub fn calculate_item(item: i32, idx: usize, total_count: f64, canvas: &HtmlCanvasElement) -> ChartItem {
+pub fn calculate_item(item: f64, max_value: f64, idx: usize, total_count: f64, width: f64, height: f64) -> ChartItem {
     let spacing = 1.;
-    let width = canvas.width() as f64;
-    let height = canvas.height() as f64;
-    let item = item as f64;
     let idx = idx as f64;
 
-    let absolute_item_value = item / total_count;
+    let absolute_item_value = if max_value > 0. { item / max_value } else { 0. };
 
     let is_width_spaceable = width > (total_count * spacing) * 2.;
     let item_height = absolute_item_value * height;
@@ -50,60 +121,325 @@ pub fn calculate_item(item: i32, idx: usize, total_count: f64, canvas: &HtmlCanv
     }
 }
 
+/// Hue running from red for the smallest share to purple for the largest
+fn share_color(share: f64) -> String {
+    format!("hsl({}, 70%, 55%)", share * 300.)
+}
+
+fn dot(context: &CanvasRenderingContext2d, x: f64, y: f64, radius: f64) {
+    context.begin_path();
+    context.arc(x, y, radius, 0., f64::consts::TAU).unwrap();
+    context.fill();
+}
+
+/// Canvas area and item values the views other than bars draw from
+struct View<'a> {
+    context: &'a CanvasRenderingContext2d,
+    width: f64,
+    height: f64,
+    /// Values as shares of the chart's value span, above 0 and up to 1
+    shares: Vec<f64>,
+    /// Color of the items the active step touches
+    highlight: &'a dyn Fn(usize) -> Option<&'static str>,
+}
+
+impl View<'_> {
+    fn center(&self) -> (f64, f64, f64) {
+        (self.width / 2., self.height / 2., (self.width.min(self.height) / 2. - DOT_RADIUS * 2.).max(0.))
+    }
+
+    // Angle of the item at idx, the first one is at the top and the rest follow clockwise
+    fn angle(&self, idx: usize) -> f64 {
+        idx as f64 / self.shares.len() as f64 * f64::consts::TAU - f64::consts::FRAC_PI_2
+    }
+
+    fn fill_dot(&self, idx: usize, x: f64, y: f64, base_color: &str) {
+        let (color, radius) = match (self.highlight)(idx) {
+            Some(color) => (color, DOT_RADIUS * 2.),
+            None => (base_color, DOT_RADIUS),
+        };
+        self.context.set_fill_style(&JsValue::from(color));
+        dot(self.context, x, y, radius);
+    }
+
+    fn draw_scatter(&self) {
+        let count = self.shares.len() as f64;
+        for (idx, &share) in self.shares.iter().enumerate() {
+            let x = (idx as f64 + 0.5) / count * self.width;
+            let y = (self.height - share * self.height).max(DOT_RADIUS * 2.);
+            self.fill_dot(idx, x, y, BAR_COLOR);
+        }
+    }
+
+    // Touched items are marked in a row above the strip, as their colors are the values
+    fn draw_color_strip(&self) {
+        let column_width = self.width / self.shares.len() as f64;
+        for (idx, &share) in self.shares.iter().enumerate() {
+            let x = idx as f64 * column_width;
+            self.context.set_fill_style(&JsValue::from(share_color(share)));
+            // Slightly wider than the column, so no seams show between them
+            self.context.fill_rect(x, STRIP_MARK_HEIGHT, column_width + 0.5, self.height - STRIP_MARK_HEIGHT);
+            if let Some(color) = (self.highlight)(idx) {
+                self.context.set_fill_style(&JsValue::from(color));
+                self.context.fill_rect(x, 0., column_width.max(2.), STRIP_MARK_HEIGHT - 2.);
+            }
+        }
+    }
+
+    // Items in place sit on the outer ring, so a sorted array is a full circle
+    fn draw_disparity_circle(&self, displacements: &[usize]) {
+        let (center_x, center_y, radius) = self.center();
+        let max_displacement = (self.shares.len().saturating_sub(1)).max(1) as f64;
+        self.context.set_stroke_style(&JsValue::from(AUX_COLOR));
+        self.context.set_line_width(1.);
+        self.context.begin_path();
+        self.context.arc(center_x, center_y, radius, 0., f64::consts::TAU).unwrap();
+        self.context.stroke();
+        for (idx, &share) in self.shares.iter().enumerate() {
+            let displacement = displacements.get(idx).copied().unwrap_or(0) as f64;
+            let distance = radius * (1. - displacement / max_displacement);
+            let angle = self.angle(idx);
+            self.fill_dot(idx, center_x + distance * angle.cos(), center_y + distance * angle.sin(), &share_color(share));
+        }
+    }
+
+    // Sorted values grow steadily with the angle, which draws one smooth turn
+    fn draw_spiral(&self) {
+        let (center_x, center_y, radius) = self.center();
+        let points: Vec<(f64, f64)> = self
+            .shares
+            .iter()
+            .enumerate()
+            .map(|(idx, &share)| {
+                let angle = self.angle(idx);
+                (center_x + radius * share * angle.cos(), center_y + radius * share * angle.sin())
+            })
+            .collect();
+        self.context.set_stroke_style(&JsValue::from(AUX_COLOR));
+        self.context.set_line_width(1.);
+        self.context.begin_path();
+        for (idx, &(x, y)) in points.iter().enumerate() {
+            if idx == 0 {
+                self.context.move_to(x, y);
+            } else {
+                self.context.line_to(x, y);
+            }
+        }
+        self.context.stroke();
+        for (idx, &(x, y)) in points.iter().enumerate() {
+            self.fill_dot(idx, x, y, &share_color(self.shares[idx]));
+        }
+    }
+}
+
 #[function_component(SortingGraphCanvas)]
 pub fn sorting_graph_canvas(props: &Props) -> Html {
     let data = props.data.clone();
+    let (min_value, max_value) = props.value_range;
+    let labels = props.labels.clone();
+    let aux = props.aux.clone();
+    let runs = props.runs.clone();
+    let tags = props.tags.clone();
+    let unstable_pairs = props.unstable_pairs.clone();
     let step_item = props.active_step_item;
+    let renderer = props.renderer;
+    let displacements = props.displacements.clone();
+    let annotations = props.annotations.clone();
     let str_to_js = |str: &str| JsValue::from(str);
-    use_effect(move || {
-        let document = web_sys::window().unwrap().document().unwrap();
-        let canvas = document.get_element_by_id("canvas").unwrap();
-        let canvas: HtmlCanvasElement = canvas
-            .dyn_into::<HtmlCanvasElement>()
-            .map_err(|_| ())
-            .unwrap();
-
-        let context = canvas
-            .get_context("2d")
-            .unwrap()
-            .unwrap()
-            .dyn_into::<CanvasRenderingContext2d>()
-            .unwrap();
-
-        context.clear_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
-        context.set_fill_style(&str_to_js("#ff5733"));
-        context.set_line_cap("round");
-        context.set_line_join("round");
-
-        let items_count = data.len() as f64;
-
-        let colored_items = match step_item {
-            SortType::Swap(idx1, idx2) => {
-                if idx1 == idx2 && idx1 == 0 {
-                    vec![]
+    // Referenced by node rather than by id, so several charts can live on one page
+    let canvas_ref = use_node_ref();
+    {
+        let canvas_ref = canvas_ref.clone();
+        use_effect(move || {
+            let canvas: HtmlCanvasElement = canvas_ref.cast::<HtmlCanvasElement>().unwrap();
+
+            let context = canvas
+                .get_context("2d")
+                .unwrap()
+                .unwrap()
+                .dyn_into::<CanvasRenderingContext2d>()
+                .unwrap();
+
+            context.clear_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+            context.set_fill_style(&str_to_js(BAR_COLOR));
+            context.set_line_cap("round");
+            context.set_line_join("round");
+
+            let items_count = data.len() as f64;
+            // The smallest value still gets a bar one step high, so 1..=n keeps its item / n heights
+            let value_step = if max_value > min_value { (max_value - min_value) / items_count } else { 1. };
+            let value_offset = min_value - value_step;
+            let value_span = max_value - value_offset;
+            let canvas_width = canvas.width() as f64;
+            let canvas_height = canvas.height() as f64;
+            // The auxiliary chart takes the bottom part of the canvas when there is one
+            let chart_height = if aux.is_empty() {
+                canvas_height
+            } else {
+                canvas_height * (1. - AUX_CHART_SHARE) - AUX_CHART_GAP
+            };
+
+            let (colored_items, color) = match step_item {
+                SortType::Swap(idx1, idx2) => {
+                    if idx1 == idx2 && idx1 == 0 {
+                        (vec![], SWAP_COLOR)
+                    } else {
+                        (vec![idx1, idx2], SWAP_COLOR)
+                    }
+                },
+                SortType::Set(idx, _value) => (vec![idx], SET_COLOR),
+                SortType::Compare(idx1, idx2) => (vec![idx1, idx2], COMPARE_COLOR),
+                SortType::Alloc(_)
+                | SortType::Free(_)
+                | SortType::Digit(_)
+                | SortType::AuxSet(..)
+                | SortType::Line(_) => (vec![], BAR_COLOR),
+                SortType::Run(lo, hi) => ((lo..hi).collect(), RUN_COLORS[0]),
+                SortType::Bucket(idx, bucket) => (vec![idx], BUCKET_COLORS[bucket % BUCKET_COLORS.len()]),
+                SortType::MarkPivot(idx) => (vec![idx], PIVOT_COLOR),
+                SortType::MarkSorted(..) | SortType::FocusRange(..) | SortType::HeapBoundary(_) => (vec![], BAR_COLOR),
+            };
+            let highlight = |idx: usize| {
+                if colored_items.contains(&idx) {
+                    Some(color)
+                } else if annotations.pivot == Some(idx) {
+                    Some(PIVOT_COLOR)
                 } else {
-                    vec![idx1, idx2]
+                    None
+                }
+            };
+            let view = View {
+                context: &context,
+                width: canvas_width,
+                height: chart_height,
+                shares: data.iter().map(|&item| (item - value_offset) / value_span).collect(),
+                highlight: &highlight,
+            };
+            match renderer {
+                Renderer::Scatter => view.draw_scatter(),
+                Renderer::ColorStrip => view.draw_color_strip(),
+                Renderer::DisparityCircle => view.draw_disparity_circle(&displacements),
+                Renderer::Spiral => view.draw_spiral(),
+                Renderer::Bars => {
+                    for (idx, &item) in data.iter().enumerate() {
+                        let ChartItem {
+                            x,
+                            y,
+                            width,
+                            height,
+                        } = calculate_item(item - value_offset, value_span, idx, items_count, canvas_width, chart_height);
+                        if let Some(color) = highlight(idx) {
+                            context.set_fill_style(&str_to_js(color));
+                        } else if let Some(tag) = tags.get(idx) {
+                            let hue = *tag as f64 / tags.len() as f64 * 300.;
+                            context.set_fill_style(&str_to_js(&format!("hsl({}, 70%, 55%)", hue)));
+                        }
+                        context.fill_rect(x, y, width, height);
+                        if tags.len() == data.len() && data.len() <= LABEL_MAX_ITEMS {
+                            context.set_fill_style(&str_to_js("white"));
+                            context.set_text_align("center");
+                            context.fill_text(&tags[idx].to_string(), x + width / 2., chart_height - 6.).unwrap();
+                        }
+                        if let Some(label) = labels.get(idx).filter(|_| data.len() <= LABEL_MAX_ITEMS) {
+                            // Written upward along the bar, inside it when it is tall enough, above it otherwise
+                            let text_width = context.measure_text(label).unwrap().width();
+                            let is_inside = height > text_width + LABEL_PADDING * 2.;
+                            context.save();
+                            context.translate(x + width / 2., y).unwrap();
+                            context.rotate(-f64::consts::FRAC_PI_2).unwrap();
+                            context.set_text_baseline("middle");
+                            if is_inside {
+                                context.set_fill_style(&str_to_js("white"));
+                                context.set_text_align("right");
+                                context.fill_text(label, -LABEL_PADDING, 0.).unwrap();
+                            } else {
+                                context.set_fill_style(&str_to_js(LABEL_COLOR));
+                                context.set_text_align("left");
+                                context.fill_text(label, LABEL_PADDING, 0.).unwrap();
+                            }
+                            context.restore();
+                        }
+                        context.set_fill_style(&str_to_js(BAR_COLOR));
+                    }
+
+                    // Equal keys that end up out of their original order are outlined and tied by an arc
+                    context.set_stroke_style(&str_to_js(UNSTABLE_COLOR));
+                    context.set_line_width(2.);
+                    for &(idx1, idx2) in unstable_pairs.iter() {
+                        let first = calculate_item(data[idx1] - value_offset, value_span, idx1, items_count, canvas_width, chart_height);
+                        let second = calculate_item(data[idx2] - value_offset, value_span, idx2, items_count, canvas_width, chart_height);
+                        context.stroke_rect(first.x, first.y, first.width, first.height);
+                        context.stroke_rect(second.x, second.y, second.width, second.height);
+                        let start = first.x + first.width / 2.;
+                        let end = second.x + second.width / 2.;
+                        let top = first.y.min(second.y);
+                        context.begin_path();
+                        context.move_to(start, first.y);
+                        context.quadratic_curve_to((start + end) / 2., (top - (end - start) / 4.).max(0.), end, second.y);
+                        context.stroke();
+                    }
+
+                    // Runs are marked with alternating strips above the bars they cover
+                    for (run_idx, &(lo, hi)) in runs.iter().enumerate() {
+                        let start = calculate_item(0., 1., lo, items_count, canvas_width, chart_height).x;
+                        let end = calculate_item(0., 1., hi, items_count, canvas_width, chart_height).x;
+                        context.set_fill_style(&str_to_js(RUN_COLORS[run_idx % RUN_COLORS.len()]));
+                        context.fill_rect(start, 0., end - start, RUN_MARK_HEIGHT);
+                    }
                 }
-            },
-            SortType::Set(idx, _value) => vec![idx],
-        };
-        for (idx, &item) in data.iter().enumerate() {
-            let ChartItem {
-                x,
-                y,
-                width,
-                height,
-            } = calculate_item(item, idx, items_count, &canvas);
-            if colored_items.contains(&idx) {
-                context.set_fill_style(&str_to_js("#53c2da"));
             }
-            context.fill_rect(x, y, width, height);
-            context.set_fill_style(&str_to_js("#ff5733"));
-        }
-    });
+
+            // Views laid out by index get the working range and finished region shaded over them
+            if matches!(renderer, Renderer::Bars | Renderer::Scatter | Renderer::ColorStrip) {
+                // Bars leave a gap between them, the scatter and strip columns do not
+                let index_x = |idx: usize| match renderer {
+                    Renderer::Bars => calculate_item(0., 1., idx, items_count, canvas_width, chart_height).x,
+                    _ => idx as f64 / items_count * canvas_width,
+                };
+                if let Some((lo, hi)) = annotations.focus {
+                    context.set_fill_style(&str_to_js(FOCUS_SHADE));
+                    context.fill_rect(index_x(lo), 0., index_x(hi) - index_x(lo), chart_height);
+                }
+                context.set_fill_style(&str_to_js(SORTED_SHADE));
+                for &(lo, hi) in annotations.sorted.iter() {
+                    context.fill_rect(index_x(lo), 0., index_x(hi) - index_x(lo), chart_height);
+                }
+                // Once the heap is down to nothing only the sorted tail is left to show
+                let heap_start = annotations.focus.map_or(0, |(lo, _)| lo);
+                if let Some(end) = annotations.heap_end.filter(|&end| end > heap_start) {
+                    context.set_stroke_style(&str_to_js(HEAP_BOUNDARY_COLOR));
+                    context.set_line_width(2.);
+                    context.begin_path();
+                    context.move_to(index_x(end), 0.);
+                    context.line_to(index_x(end), chart_height);
+                    context.stroke();
+                }
+            }
+
+            let aux_count = aux.len() as f64;
+            let aux_max = aux.iter().max().copied().unwrap_or(0) as f64;
+            let aux_top = canvas_height * (1. - AUX_CHART_SHARE);
+            let active_aux = match step_item {
+                SortType::AuxSet(idx, _) => Some(idx),
+                _ => None,
+            };
+            for (idx, &item) in aux.iter().enumerate() {
+                let ChartItem {
+                    x,
+                    y,
+                    width,
+                    height,
+                } = calculate_item(item as f64, aux_max, idx, aux_count, canvas_width, canvas_height - aux_top);
+                let color = if active_aux == Some(idx) { SET_COLOR } else { AUX_COLOR };
+                context.set_fill_style(&str_to_js(color));
+                context.fill_rect(x, aux_top + y, width, height);
+            }
+            context.set_fill_style(&str_to_js(BAR_COLOR));
+        });
+    }
     html! {
         <>
-            <canvas id="canvas" class="w-full block" width="950" height="500" />
+            <canvas ref={canvas_ref} class="w-full block" width={props.width.to_string()} height={props.height.to_string()} />
         </>
     }
 }
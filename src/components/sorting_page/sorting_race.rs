@@ -0,0 +1,234 @@
+use yew::prelude::*;
+use yew_hooks::use_interval;
+
+use algovis::config::sort_config::SortConfigValues;
+use algovis::helpers::random_seed;
+use algovis::services::playable::Playable;
+use algovis::services::race::{Race, Racer, MAX_RACERS, MIN_RACERS};
+
+use crate::components::sorting_page::sorting_graph_canvas::{Renderer, SortingGraphCanvas};
+use crate::components::ui::the_button::TheButton;
+use crate::components::ui::the_slider::TheSlider;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub config: SortConfigValues,
+    #[prop_or_default]
+    pub on_change: Callback<SortConfigValues>,
+}
+
+fn default_algorithms(config: &SortConfigValues) -> Vec<String> {
+    config
+        .algorithm_options()
+        .iter()
+        .take(MIN_RACERS)
+        .map(|option| option.value.clone())
+        .collect()
+}
+
+fn racer_card(racer: &Racer, renderer: Renderer) -> Html {
+    let sorter = &racer.sorter;
+    let stats = sorter.active_stats();
+    let rank = match racer.rank {
+        Some(rank) => format!("#{}", rank),
+        None => "...".to_string(),
+    };
+    html! {
+        <div class="flex flex-col gap-1 p-2 border-2 border-accent rounded-lg">
+            <div class="flex justify-between font-semibold">
+                <span>{racer.label.clone()}</span>
+                <span>{rank}</span>
+            </div>
+            <div class="flex flex-wrap gap-x-3 text-sm">
+                <div>{format!("Steps: {}/{}", sorter.get_active_step_string(), sorter.get_steps_len_string())}</div>
+                <div>{format!("Comparisons: {}", stats.comparisons)}</div>
+                <div>{format!("Swaps: {}", stats.swaps)}</div>
+                <div>{format!("Writes: {}", stats.writes)}</div>
+            </div>
+            <SortingGraphCanvas
+                data={sorter.magnitudes()}
+                value_range={sorter.value_range()}
+                labels={sorter.labels()}
+                aux={sorter.aux()}
+                runs={sorter.active_runs()}
+                active_step_item={sorter.get_active_step_item()}
+                renderer={renderer}
+                displacements={sorter.displacements()}
+                annotations={sorter.annotations()}
+                width={475}
+                height={250}
+            />
+        </div>
+    }
+}
+
+#[function_component(SortingRace)]
+pub fn sorting_race(props: &Props) -> Html {
+    let algorithms = use_state(|| default_algorithms(&props.config));
+    let race: UseStateHandle<Race> = {
+        let config = props.config.clone();
+        let algorithms = (*algorithms).clone();
+        use_state(move || Race::new(&config, &algorithms))
+    };
+
+    // A new size, distribution, seed or order makes a new race, the view only redraws it
+    {
+        let race = race.clone();
+        let algorithms = algorithms.clone();
+        let race_config = SortConfigValues { renderer: String::new(), ..props.config.clone() };
+        let config = props.config.clone();
+        use_effect_with_deps(
+            move |_| {
+                // Switching to items without integer keys drops the racers that need them
+                let options = config.algorithm_options();
+                let mut selected: Vec<String> = (*algorithms)
+                    .iter()
+                    .filter(|name| options.iter().any(|option| option.value == **name))
+                    .cloned()
+                    .collect();
+                if selected.len() < MIN_RACERS {
+                    selected = default_algorithms(&config);
+                }
+                race.set(Race::new(&config, &selected));
+                algorithms.set(selected);
+                || ()
+            },
+            race_config,
+        );
+    }
+
+    {
+        let race = race.clone();
+        let tick_time = race.tick_time();
+
+        use_interval(
+            move || {
+                let mut race_value = (*race).clone();
+                race_value.tick();
+                race.set(race_value);
+            },
+            tick_time,
+        );
+    }
+
+    let toggle_algorithm = {
+        let algorithms = algorithms.clone();
+        let race = race.clone();
+        let config = props.config.clone();
+        Callback::from(move |algorithm_name: String| {
+            let mut selected = (*algorithms).clone();
+            if let Some(position) = selected.iter().position(|name| *name == algorithm_name) {
+                if selected.len() <= MIN_RACERS {
+                    return;
+                }
+                selected.remove(position);
+            } else {
+                if selected.len() >= MAX_RACERS {
+                    return;
+                }
+                selected.push(algorithm_name);
+            }
+            race.set(Race::new(&config, &selected));
+            algorithms.set(selected);
+        })
+    };
+
+    let renderer = Renderer::from_string(&props.config.renderer).unwrap_or(Renderer::Bars);
+
+    // A fresh seed changes the config, which rebuilds the race above
+    let handle_generate = {
+        let on_change = props.on_change.clone();
+        let config = props.config.clone();
+        Callback::from(move |_| {
+            let mut config_value = config.clone();
+            config_value.seed = random_seed();
+            on_change.emit(config_value);
+        })
+    };
+
+    let handle_play = {
+        let race = race.clone();
+        let config = props.config.clone();
+        Callback::from(move |_| {
+            let mut race_value = (*race).clone();
+            race_value.play(&config);
+            race.set(race_value);
+        })
+    };
+
+    let handle_pause = {
+        let race = race.clone();
+        Callback::from(move |_| {
+            let mut race_value = (*race).clone();
+            race_value.stop();
+            race.set(race_value);
+        })
+    };
+
+    let change_current_step = {
+        let race = race.clone();
+        Callback::from(move |value: u32| {
+            let mut race_value = (*race).clone();
+            race_value.set_step(value);
+            race.set(race_value);
+        })
+    };
+
+    let algorithm_buttons = props
+        .config
+        .algorithm_options()
+        .iter()
+        .map(|option| {
+            let value = option.value.clone();
+            let onclick = {
+                let toggle_algorithm = toggle_algorithm.clone();
+                let value = value.clone();
+                Callback::from(move |_| toggle_algorithm.emit(value.clone()))
+            };
+            html! {
+                <TheButton class="!w-auto" active={algorithms.contains(&value)} onclick={onclick}>
+                    {option.label.clone()}
+                </TheButton>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="w-full flex flex-col gap-3">
+            <div class="flex flex-wrap gap-2">
+                {algorithm_buttons}
+            </div>
+            <div class="text-sm">
+                {format!("Pick {} to {} algorithms, they all sort the same input", MIN_RACERS, MAX_RACERS)}
+            </div>
+            <div class="flex gap-2">
+                <TheButton onclick={handle_generate}>
+                    {"Generate"}
+                </TheButton>
+                {
+                    if race.is_playing {
+                        html! {
+                            <TheButton onclick={handle_pause}>
+                                {"Pause"}
+                            </TheButton>
+                        }
+                    } else {
+                        html! {
+                            <TheButton onclick={handle_play}>
+                                {"Play"}
+                            </TheButton>
+                        }
+                    }
+                }
+            </div>
+            <div class="grid grid-cols-1 lg:grid-cols-2 gap-3">
+                {race.racers.iter().map(|racer| racer_card(racer, renderer)).collect::<Html>()}
+            </div>
+            <TheSlider
+                max={race.max_steps().to_string()}
+                value={race.active_step}
+                set_value={change_current_step}
+            />
+        </div>
+    }
+}
@@ -0,0 +1,35 @@
+use wasm_bindgen::JsValue;
+use web_sys::{AudioContext, OscillatorType};
+
+use algovis::services::sonification::Tone;
+
+const VOLUME: f32 = 0.1;
+
+/// Plays tones through Web Audio, has to be created from a user gesture for browsers to allow sound
+pub struct Synth {
+    context: AudioContext,
+}
+
+impl Synth {
+    pub fn new() -> Result<Synth, JsValue> {
+        Ok(Synth { context: AudioContext::new()? })
+    }
+
+    /// One oscillator per tone, fading out so that back to back tones do not click
+    pub fn play(&self, tone: Tone) -> Result<(), JsValue> {
+        let start = self.context.current_time();
+        let end = start + tone.duration_ms / 1000.;
+
+        let oscillator = self.context.create_oscillator()?;
+        oscillator.set_type(OscillatorType::Triangle);
+        oscillator.frequency().set_value(tone.frequency as f32);
+        let gain = self.context.create_gain()?;
+        gain.gain().set_value_at_time(VOLUME, start)?;
+        gain.gain().linear_ramp_to_value_at_time(0., end)?;
+
+        oscillator.connect_with_audio_node(&gain)?;
+        gain.connect_with_audio_node(&self.context.destination())?;
+        oscillator.start()?;
+        oscillator.stop_with_when(end)
+    }
+}
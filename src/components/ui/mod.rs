@@ -2,3 +2,6 @@ pub mod the_input;
 pub mod the_select;
 pub mod the_button;
 pub mod the_slider;
+pub mod the_step_controls;
+pub mod the_pseudocode;
+pub mod the_trace_controls;
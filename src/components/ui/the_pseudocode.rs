@@ -0,0 +1,31 @@
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub lines: Vec<&'static str>,
+    #[prop_or_default]
+    pub active_line: Option<usize>,
+}
+
+#[function_component(ThePseudocode)]
+pub fn the_pseudocode(props: &Props) -> Html {
+    let lines = props.lines.iter().enumerate().map(|(idx, line)| {
+        let class = if props.active_line == Some(idx) {
+            "bg-accent text-white"
+        } else {
+            ""
+        };
+        html! {
+            <div class={classes!("flex", "gap-2", "px-2", "rounded", class)}>
+                <span class="w-6 shrink-0 text-right opacity-60">{idx + 1}</span>
+                <span class="whitespace-pre">{*line}</span>
+            </div>
+        }
+    }).collect::<Html>();
+
+    html! {
+        <div class="mt-3 p-2 border-2 border-accent rounded-lg font-mono text-sm overflow-x-auto">
+            {lines}
+        </div>
+    }
+}
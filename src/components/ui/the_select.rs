@@ -1,10 +1,6 @@
 use yew::prelude::*;
 
-#[derive(PartialEq, Clone)]
-pub struct SelectOption {
-    pub value: String,
-    pub label: String,
-}
+use algovis::config::select_option::SelectOption;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
@@ -0,0 +1,74 @@
+use wasm_bindgen::prelude::*;
+use web_sys::{HtmlElement, KeyboardEvent};
+use yew::prelude::*;
+
+use algovis::services::playable::PlaybackCommand;
+
+use crate::components::ui::the_button::TheButton;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub on_command: Callback<PlaybackCommand>,
+}
+
+// Keys typed into form fields belong to the field, not to playback
+fn is_typing_target(event: &KeyboardEvent) -> bool {
+    event
+        .target()
+        .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+        .map(|element| matches!(element.tag_name().as_str(), "INPUT" | "SELECT" | "TEXTAREA"))
+        .unwrap_or(false)
+}
+
+/// Step buttons plus page-wide keys: space, arrows, Home and End
+#[function_component(TheStepControls)]
+pub fn the_step_controls(props: &Props) -> Html {
+    {
+        // The listener is registered once and reads the callback of the latest render
+        let on_command = use_mut_ref(|| props.on_command.clone());
+        *on_command.borrow_mut() = props.on_command.clone();
+        use_effect_with_deps(
+            move |_| {
+                let listener = Closure::<dyn Fn(KeyboardEvent)>::new(move |event: KeyboardEvent| {
+                    if (event.repeat() && event.key() == " ") || is_typing_target(&event) {
+                        return;
+                    }
+                    if let Ok(command) = PlaybackCommand::from_key(&event.key()) {
+                        event.prevent_default();
+                        on_command.borrow().emit(command);
+                    }
+                });
+                let window = web_sys::window().unwrap();
+                window
+                    .add_event_listener_with_callback("keydown", listener.as_ref().unchecked_ref())
+                    .unwrap();
+                move || {
+                    window
+                        .remove_event_listener_with_callback("keydown", listener.as_ref().unchecked_ref())
+                        .unwrap();
+                }
+            },
+            (),
+        );
+    }
+
+    let button = |label: &'static str, title: &'static str, command: PlaybackCommand| {
+        let on_command = props.on_command.clone();
+        html! {
+            <div title={title} class="w-full">
+                <TheButton onclick={Callback::from(move |_| on_command.emit(command))}>
+                    {label}
+                </TheButton>
+            </div>
+        }
+    };
+
+    html! {
+        <div class="flex gap-2 mt-2">
+            {button("|<", "Jump to start (Home)", PlaybackCommand::JumpToStart)}
+            {button("<", "Step back (Left arrow)", PlaybackCommand::StepBack)}
+            {button(">", "Step forward (Right arrow)", PlaybackCommand::StepForward)}
+            {button(">|", "Jump to end (End)", PlaybackCommand::JumpToEnd)}
+        </div>
+    }
+}
@@ -0,0 +1,103 @@
+use wasm_bindgen::prelude::*;
+use web_sys::{Blob, BlobPropertyBag, FileReader, HtmlAnchorElement, HtmlInputElement, Url};
+use yew::prelude::*;
+
+use algovis::services::trace::TraceFormat;
+
+use crate::components::ui::the_button::TheButton;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// Serializes the current trace in the requested format
+    pub on_export: Callback<TraceFormat, String>,
+    /// Loads a JSON trace, returns why it was refused if it was
+    pub on_import: Callback<String, Option<String>>,
+    #[prop_or(String::from("trace"))]
+    pub file_name: String,
+}
+
+fn download(contents: &str, file_name: &str, format: TraceFormat) {
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let blob = Blob::new_with_str_sequence_and_options(&parts, BlobPropertyBag::new().type_(format.mime_type())).unwrap();
+    let url = Url::create_object_url_with_blob(&blob).unwrap();
+    let anchor: HtmlAnchorElement = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("a").ok())
+        .and_then(|element| element.dyn_into().ok())
+        .unwrap();
+    anchor.set_href(&url);
+    anchor.set_download(&format!("{}.{}", file_name, format.extension()));
+    anchor.click();
+    Url::revoke_object_url(&url).unwrap();
+}
+
+/// Download buttons for the recorded trace plus a JSON trace picker
+#[function_component(TheTraceControls)]
+pub fn the_trace_controls(props: &Props) -> Html {
+    let file_input = use_node_ref();
+    let error = use_state(|| None::<String>);
+
+    let export = |format: TraceFormat| {
+        let on_export = props.on_export.clone();
+        let file_name = props.file_name.clone();
+        Callback::from(move |_| download(&on_export.emit(format), &file_name, format))
+    };
+
+    let pick_file = {
+        let file_input = file_input.clone();
+        Callback::from(move |_| {
+            if let Some(input) = file_input.cast::<HtmlInputElement>() {
+                input.click();
+            }
+        })
+    };
+
+    let read_file = {
+        let on_import = props.on_import.clone();
+        let error = error.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            // Cleared so picking the same file again still fires a change
+            input.set_value("");
+            let reader = FileReader::new().unwrap();
+            let onload = {
+                let reader = reader.clone();
+                let on_import = on_import.clone();
+                let error = error.clone();
+                Closure::once_into_js(move || {
+                    let text = reader.result().ok().and_then(|result| result.as_string()).unwrap_or_default();
+                    error.set(on_import.emit(text));
+                })
+            };
+            reader.set_onload(Some(onload.unchecked_ref()));
+            reader.read_as_text(&file).unwrap();
+        })
+    };
+
+    html! {
+        <div class="flex flex-col gap-2">
+            <div class="flex gap-2">
+                <TheButton onclick={export(TraceFormat::Json)}>
+                    {"Export JSON"}
+                </TheButton>
+                <TheButton onclick={export(TraceFormat::Csv)}>
+                    {"Export CSV"}
+                </TheButton>
+            </div>
+            <TheButton onclick={pick_file}>
+                {"Import trace"}
+            </TheButton>
+            <input ref={file_input} type="file" accept=".json,application/json" class="hidden" onchange={read_file} />
+            {
+                if let Some(message) = &*error {
+                    html! { <div class="text-sm text-red-500">{message.clone()}</div> }
+                } else {
+                    html! {}
+                }
+            }
+        </div>
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::select_option::SelectOption;
+use crate::helpers::random_seed;
+use crate::services::maze_generator::Cell;
+
+// Only the chosen values are serialized, the editing tool and option lists come from `new`
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MazeConfigValues {
+    pub time_overall: i32,
+    pub current_algorithm_name: String,
+    pub size: usize,
+    #[serde(skip)]
+    pub cell_type: Cell,
+    pub current_step: u32,
+    pub seed: u64,
+    #[serde(skip)]
+    pub alg_options: Vec<SelectOption>,
+}
+
+impl MazeConfigValues {
+    pub fn new() -> Self {
+        let default_algorithm = "dfs".to_string();
+        Self {
+            time_overall: 2,
+            size: 35,
+            cell_type: Cell::Entry,
+            current_algorithm_name: default_algorithm.clone(),
+            alg_options: vec![
+                SelectOption { value: default_algorithm, label: String::from("DFS"), },
+                SelectOption { value: "dijkstra".to_string(), label: String::from("Dijkstra"), },
+            ],
+            current_step: 0,
+            seed: random_seed(),
+        }
+    }
+}
+
+impl Default for MazeConfigValues {
+    fn default() -> Self {
+        Self::new()
+    }
+}
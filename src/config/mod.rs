@@ -0,0 +1,3 @@
+pub mod select_option;
+pub mod sort_config;
+pub mod maze_config;
@@ -0,0 +1,5 @@
+#[derive(PartialEq, Clone, Debug)]
+pub struct SelectOption {
+    pub value: String,
+    pub label: String,
+}
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::select_option::SelectOption;
+use crate::helpers::{parse_custom_input, random_seed};
+use crate::services::sort_item::{ItemKind, Record, SortItem};
+use crate::services::sort_key::SortKey;
+use crate::services::sorter::{allocates_per_key, needs_int_key};
+use crate::sorting_algorithms::MAX_KEY_SPAN;
+
+const MAX_ITEMS: i32 = 100;
+
+// Only the chosen values are serialized, option lists always come from `new`
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SortConfigValues {
+    pub items_count: i32,
+    pub time_overall: i32,
+    pub current_algorithm_name: String,
+    #[serde(skip)]
+    pub alg_options: Vec<SelectOption>,
+    pub distribution: String,
+    #[serde(skip)]
+    pub distribution_options: Vec<SelectOption>,
+    pub input_mode: String,
+    #[serde(skip)]
+    pub input_mode_options: Vec<SelectOption>,
+    pub custom_input: String,
+    pub item_kind: String,
+    #[serde(skip)]
+    pub item_kind_options: Vec<SelectOption>,
+    pub sort_order: String,
+    #[serde(skip)]
+    pub sort_order_options: Vec<SelectOption>,
+    pub key_mode: String,
+    #[serde(skip)]
+    pub key_mode_options: Vec<SelectOption>,
+    pub radix_base: u32,
+    #[serde(skip)]
+    pub radix_options: Vec<SelectOption>,
+    pub partition_scheme: String,
+    #[serde(skip)]
+    pub partition_options: Vec<SelectOption>,
+    pub pivot_choice: String,
+    #[serde(skip)]
+    pub pivot_options: Vec<SelectOption>,
+    pub renderer: String,
+    #[serde(skip)]
+    pub renderer_options: Vec<SelectOption>,
+    pub current_step: u32,
+    pub seed: u64,
+}
+
+impl SortConfigValues {
+    pub fn new() -> Self {
+        let default_algorithm = "merge_sort".to_string();
+        Self {
+            items_count: MAX_ITEMS,
+            time_overall: 10,
+            current_algorithm_name: default_algorithm.clone(),
+            alg_options: vec![
+                SelectOption { value: default_algorithm, label: String::from("Merge Sort") },
+                SelectOption { value: String::from("bubble_sort"), label: String::from("Bubble Sort") },
+                SelectOption { value: String::from("heap_sort"), label: String::from("Heap Sort") },
+                SelectOption { value: String::from("quick_sort"), label: String::from("Quick Sort") },
+                SelectOption { value: String::from("insertion_sort"), label: String::from("Insertion Sort") },
+                SelectOption { value: String::from("shell_sort"), label: String::from("Shell Sort") },
+                SelectOption { value: String::from("bucket_sort"), label: String::from("Bucket Sort") },
+                SelectOption { value: String::from("radix_sort_lsd"), label: String::from("Radix Sort (LSD)") },
+                SelectOption { value: String::from("radix_sort_msd"), label: String::from("Radix Sort (MSD)") },
+                SelectOption { value: String::from("counting_sort"), label: String::from("Counting Sort") },
+                SelectOption { value: String::from("pigeonhole_sort"), label: String::from("Pigeonhole Sort") },
+                SelectOption { value: String::from("intro_sort"), label: String::from("Introsort") },
+                SelectOption { value: String::from("pdq_sort"), label: String::from("Pattern-defeating Quicksort") },
+                SelectOption { value: String::from("tim_sort"), label: String::from("TimSort") },
+            ],
+            distribution: String::from("shuffled"),
+            distribution_options: vec![
+                SelectOption { value: String::from("shuffled"), label: String::from("Shuffled") },
+                SelectOption { value: String::from("sorted"), label: String::from("Sorted") },
+                SelectOption { value: String::from("reversed"), label: String::from("Reversed") },
+                SelectOption { value: String::from("nearly_sorted"), label: String::from("Nearly sorted") },
+                SelectOption { value: String::from("few_unique"), label: String::from("Few unique") },
+                SelectOption { value: String::from("sawtooth"), label: String::from("Sawtooth") },
+                SelectOption { value: String::from("organ_pipe"), label: String::from("Organ pipe") },
+                SelectOption { value: String::from("gaussian"), label: String::from("Gaussian") },
+                SelectOption { value: String::from("partitioned_halves"), label: String::from("Partitioned halves") },
+            ],
+            input_mode: String::from("generated"),
+            input_mode_options: vec![
+                SelectOption { value: String::from("generated"), label: String::from("Generated") },
+                SelectOption { value: String::from("custom"), label: String::from("Custom values") },
+                SelectOption { value: String::from("stability"), label: String::from("Stability demo (repeated keys)") },
+            ],
+            custom_input: String::new(),
+            item_kind: String::from("integers"),
+            item_kind_options: vec![
+                SelectOption { value: String::from("integers"), label: String::from("Whole numbers") },
+                SelectOption { value: String::from("floats"), label: String::from("Decimal numbers") },
+                SelectOption { value: String::from("words"), label: String::from("Words") },
+                SelectOption { value: String::from("records"), label: String::from("Records (key:name)") },
+            ],
+            sort_order: String::from("ascending"),
+            sort_order_options: vec![
+                SelectOption { value: String::from("ascending"), label: String::from("Ascending") },
+                SelectOption { value: String::from("descending"), label: String::from("Descending") },
+            ],
+            key_mode: String::from("value"),
+            key_mode_options: vec![
+                SelectOption { value: String::from("value"), label: String::from("Value") },
+                SelectOption { value: String::from("last_digit"), label: String::from("Last digit") },
+                SelectOption { value: String::from("parity_then_value"), label: String::from("Parity, then value") },
+            ],
+            radix_base: 10,
+            radix_options: [2, 4, 10, 16]
+                .iter()
+                .map(|base| SelectOption { value: base.to_string(), label: format!("Base {}", base) })
+                .collect(),
+            partition_scheme: String::from("lomuto"),
+            partition_options: vec![
+                SelectOption { value: String::from("lomuto"), label: String::from("Lomuto") },
+                SelectOption { value: String::from("hoare"), label: String::from("Hoare") },
+                SelectOption { value: String::from("three_way"), label: String::from("Three-way (Dutch flag)") },
+            ],
+            pivot_choice: String::from("middle"),
+            pivot_options: vec![
+                SelectOption { value: String::from("first"), label: String::from("First") },
+                SelectOption { value: String::from("last"), label: String::from("Last") },
+                SelectOption { value: String::from("middle"), label: String::from("Middle") },
+                SelectOption { value: String::from("median_of_three"), label: String::from("Median of three") },
+                SelectOption { value: String::from("random"), label: String::from("Random") },
+            ],
+            renderer: String::from("bars"),
+            renderer_options: vec![
+                SelectOption { value: String::from("bars"), label: String::from("Bars") },
+                SelectOption { value: String::from("scatter"), label: String::from("Scatter plot") },
+                SelectOption { value: String::from("color_strip"), label: String::from("Color strip") },
+                SelectOption { value: String::from("disparity_circle"), label: String::from("Disparity circle") },
+                SelectOption { value: String::from("spiral"), label: String::from("Spiral") },
+            ],
+            current_step: 0,
+            seed: random_seed(),
+        }
+    }
+
+    pub fn item_kind(&self) -> ItemKind {
+        ItemKind::from_string(&self.item_kind).unwrap_or(ItemKind::Integers)
+    }
+
+    /// Algorithms that can sort the chosen kind of items
+    pub fn algorithm_options(&self) -> Vec<SelectOption> {
+        let has_int_key = self.item_kind().has_int_key();
+        self.alg_options
+            .iter()
+            .filter(|option| has_int_key || !needs_int_key(&option.value))
+            .cloned()
+            .collect()
+    }
+
+    pub fn algorithm_label(&self, algorithm_name: &str) -> String {
+        self.alg_options
+            .iter()
+            .find(|option| option.value == algorithm_name)
+            .map(|option| option.label.clone())
+            .unwrap_or_else(|| algorithm_name.to_string())
+    }
+
+    pub fn distribution_label(&self, distribution_name: &str) -> String {
+        self.distribution_options
+            .iter()
+            .find(|option| option.value == distribution_name)
+            .map(|option| option.label.clone())
+            .unwrap_or_else(|| distribution_name.to_string())
+    }
+
+    pub fn is_custom_input(&self) -> bool {
+        self.input_mode == "custom"
+    }
+
+    pub fn is_stability_demo(&self) -> bool {
+        self.input_mode == "stability"
+    }
+
+    /// Why the chosen algorithm falls back to merge sort on the custom input, if it does
+    pub fn key_span_warning(&self) -> Option<String> {
+        if !self.is_custom_input() || !allocates_per_key(&self.current_algorithm_name) {
+            return None;
+        }
+        let key_span = match self.item_kind() {
+            ItemKind::Integers => self.custom_key_span::<i32>(),
+            ItemKind::Records => self.custom_key_span::<Record>(),
+            ItemKind::Floats | ItemKind::Words => 0,
+        };
+        self.key_span_fallback(key_span)
+    }
+
+    /// Why the chosen algorithm merge sorts keys spanning `key_span` values, if it does
+    pub fn key_span_fallback(&self, key_span: u64) -> Option<String> {
+        (allocates_per_key(&self.current_algorithm_name) && key_span > MAX_KEY_SPAN).then(|| {
+            format!(
+                "Keys span {} values, {} keeps a slot for at most {} and merge sorts them instead",
+                key_span,
+                self.algorithm_label(&self.current_algorithm_name),
+                MAX_KEY_SPAN
+            )
+        })
+    }
+
+    // Spans the keys the items are actually sorted by, parity then value about doubles the values' span
+    fn custom_key_span<T: SortItem>(&self) -> u64 {
+        let items = parse_custom_input::<T>(&self.custom_input).unwrap_or_default();
+        SortKey::for_items(self, &items).key_span(&items)
+    }
+}
+
+impl Default for SortConfigValues {
+    fn default() -> Self {
+        Self::new()
+    }
+}
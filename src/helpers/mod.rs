@@ -1,5 +1,8 @@
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng, SeedableRng};
+
+use crate::services::sort_item::SortItem;
 
 pub const MAX_REFRESH_RATE: f32 = 33.33;
 pub const MS_IN_SECS: f32 = 1000.;
@@ -14,12 +17,171 @@ pub fn parse_string_to_i32_or_default(input: String, default: i32) -> i32 {
     }
 }
 
-pub fn shuffle<T>(mut data: Vec<T>) -> Vec<T> {
-    let mut rng = thread_rng();
-    data.shuffle(&mut rng);
+pub fn parse_string_to_u64_or_default(input: String, default: u64) -> u64 {
+    input.trim().parse::<u64>().unwrap_or(default)
+}
+
+/// Parses a comma or whitespace separated list of values typed by the user
+pub fn parse_custom_input<T: SortItem>(input: &str) -> Result<Vec<T>, String> {
+    let values = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|value| !value.is_empty())
+        .map(T::parse)
+        .collect::<Result<Vec<T>, String>>()?;
+    if values.is_empty() {
+        return Err(String::from("Enter at least one value"));
+    }
+    Ok(values)
+}
+
+/// Seed for a fresh run, shown to the user so the run can be reproduced later
+pub fn random_seed() -> u64 {
+    thread_rng().gen_range(0..1_000_000)
+}
+
+pub fn rng_from_seed(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+pub fn shuffle<T>(mut data: Vec<T>, rng: &mut impl Rng) -> Vec<T> {
+    data.shuffle(rng);
     data
 }
 
-pub fn get_new_generation(items_count: &i32) -> Vec<i32> {
-    shuffle((1..=*items_count).collect())
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Distribution {
+    Shuffled,
+    Sorted,
+    Reversed,
+    NearlySorted,
+    FewUnique,
+    Sawtooth,
+    OrganPipe,
+    Gaussian,
+    PartitionedHalves,
+}
+
+impl Distribution {
+    pub fn from_string(s: &str) -> Result<Distribution, &'static str> {
+        match s {
+            "shuffled" => Ok(Distribution::Shuffled),
+            "sorted" => Ok(Distribution::Sorted),
+            "reversed" => Ok(Distribution::Reversed),
+            "nearly_sorted" => Ok(Distribution::NearlySorted),
+            "few_unique" => Ok(Distribution::FewUnique),
+            "sawtooth" => Ok(Distribution::Sawtooth),
+            "organ_pipe" => Ok(Distribution::OrganPipe),
+            "gaussian" => Ok(Distribution::Gaussian),
+            "partitioned_halves" => Ok(Distribution::PartitionedHalves),
+            _ => Err("Invalid variant"),
+        }
+    }
+}
+
+const FEW_UNIQUE_VALUES: i32 = 5;
+const SAWTOOTH_TEETH: i32 = 4;
+
+// Every distribution keeps values within 1..=items_count, so they fit the chart
+pub fn get_new_generation(items_count: &i32, distribution: &Distribution, seed: u64) -> Vec<i32> {
+    let n = (*items_count).max(0);
+    let mut rng = rng_from_seed(seed);
+    match distribution {
+        Distribution::Shuffled => shuffle((1..=n).collect(), &mut rng),
+        Distribution::Sorted => (1..=n).collect(),
+        Distribution::Reversed => (1..=n).rev().collect(),
+        Distribution::NearlySorted => {
+            let mut data: Vec<i32> = (1..=n).collect();
+            if n > 1 {
+                for _ in 0..(n / 20).max(1) {
+                    let (a, b) = (rng.gen_range(0..n as usize), rng.gen_range(0..n as usize));
+                    data.swap(a, b);
+                }
+            }
+            data
+        }
+        Distribution::FewUnique => {
+            // Products can pass i32::MAX on large arrays, only the quotients fit
+            let values = FEW_UNIQUE_VALUES.min(n).max(1) as i64;
+            (0..n)
+                .map(|_| (rng.gen_range(1..=values) * n as i64 / values).max(1) as i32)
+                .collect()
+        }
+        Distribution::Sawtooth => {
+            let tooth = (n / SAWTOOTH_TEETH).max(1) as i64;
+            (0..n as i64).map(|idx| ((idx % tooth + 1) * n as i64 / tooth) as i32).collect()
+        }
+        Distribution::OrganPipe => {
+            (0..n).map(|idx| (idx.min(n - 1 - idx) * 2 + 1).min(n)).collect()
+        }
+        Distribution::Gaussian => {
+            let (mean, deviation) = (n as f64 / 2., n as f64 / 6.);
+            (0..n)
+                .map(|_| {
+                    // Box-Muller transform of two uniform samples
+                    let (u1, u2): (f64, f64) = (rng.gen_range(f64::EPSILON..1.), rng.gen());
+                    let normal = (-2. * u1.ln()).sqrt() * (2. * std::f64::consts::PI * u2).cos();
+                    ((mean + normal * deviation).round() as i32).clamp(1, n)
+                })
+                .collect()
+        }
+        Distribution::PartitionedHalves => {
+            let half = n / 2;
+            let mut data = shuffle((1..=half).collect(), &mut rng);
+            data.extend(shuffle((half + 1..=n).collect(), &mut rng));
+            data
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generations_stay_in_range() {
+        let distributions = [
+            Distribution::Shuffled,
+            Distribution::Sorted,
+            Distribution::Reversed,
+            Distribution::NearlySorted,
+            Distribution::FewUnique,
+            Distribution::Sawtooth,
+            Distribution::OrganPipe,
+            Distribution::Gaussian,
+            Distribution::PartitionedHalves,
+        ];
+        for items_count in [0, 1, 2, 7, 100] {
+            for distribution in distributions.iter() {
+                let data = get_new_generation(&items_count, distribution, 42);
+                assert_eq!(data.len(), items_count as usize, "{:?}", distribution);
+                assert!(data.iter().all(|&item| item >= 1 && item <= items_count), "{:?}", distribution);
+                assert_eq!(data, get_new_generation(&items_count, distribution, 42), "{:?}", distribution);
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_generations_do_not_overflow() {
+        let items_count = 100_000;
+        for distribution in [Distribution::FewUnique, Distribution::Sawtooth, Distribution::OrganPipe] {
+            let data = get_new_generation(&items_count, &distribution, 42);
+            assert!(data.iter().all(|&item| item >= 1 && item <= items_count), "{:?}", distribution);
+        }
+        assert_eq!(get_new_generation(&items_count, &Distribution::Sawtooth, 0).iter().max(), Some(&items_count));
+    }
+
+    #[test]
+    fn test_different_seeds_give_different_shuffles() {
+        let first = get_new_generation(&100, &Distribution::Shuffled, 1);
+        let second = get_new_generation(&100, &Distribution::Shuffled, 2);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_parse_custom_input() {
+        assert_eq!(parse_custom_input("3, -1,2  2\n10"), Ok(vec![3, -1, 2, 2, 10]));
+        assert_eq!(parse_custom_input("1,,2,"), Ok(vec![1, 2]));
+        assert!(parse_custom_input::<i32>("1, two, 3").unwrap_err().contains("two"));
+        assert!(parse_custom_input::<i32>(" , ").is_err());
+    }
 }
@@ -0,0 +1,5 @@
+pub mod config;
+pub mod helpers;
+pub mod maze_solver_algorithms;
+pub mod services;
+pub mod sorting_algorithms;
@@ -1,6 +1,17 @@
 use std::collections::VecDeque;
 
-use crate::{services::{maze_generator::{Maze, Cell}, mazer::{MazeStep, MazeSolverReturnType}}, components::maze_page::maze_view_canvas::Coords};
+use crate::services::{maze_generator::{Maze, Cell, Coords}, mazer::{MazeStep, MazeSolverReturnType}};
+
+pub const PSEUDOCODE: &[&str] = &[
+    "dfs(cell):",
+    "    if cell is the exit: return true",
+    "    mark cell visited",
+    "    for each open, unvisited neighbour",
+    "        if dfs(neighbour)",
+    "            add cell to the path",
+    "            return true",
+    "    return false",
+];
 
 pub fn solve_maze_by_dfs(
     maze: &Maze,
@@ -31,6 +42,7 @@ pub fn dfs_path_exists(
     steps.push_front(MazeStep {
         coords: Coords::from(col, row),
         cell_type: Cell::Visited,
+        line: 2,
     });
     
 
@@ -44,15 +56,15 @@ pub fn dfs_path_exists(
             && nc < maze.cells[nr].len()
             && !visited[nr][nc]
             && maze.cells[nr][nc] != Cell::Wall
+            && dfs_path_exists(maze, visited, Coords::from(nc, nr), end, path, steps)
         {
-            if dfs_path_exists(maze, visited, Coords::from(nc, nr), end, path, steps) {
-                path.push((row, col));
-                steps.push_front(MazeStep {
-                    coords: Coords::from(col, row),
-                    cell_type: Cell::Path,
-                });
-                return true;
-            }
+            path.push((row, col));
+            steps.push_front(MazeStep {
+                coords: Coords::from(col, row),
+                cell_type: Cell::Path,
+                line: 5,
+            });
+            return true;
         }
     }
 
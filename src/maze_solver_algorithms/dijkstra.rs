@@ -3,7 +3,7 @@ use std::cmp::Ordering;
 use std::collections::VecDeque;
 
 use crate::services::mazer::MazeSolverReturnType;
-use crate::{services::{maze_generator::{Maze, Cell}, mazer::MazeStep}, components::maze_page::maze_view_canvas::Coords};
+use crate::services::{maze_generator::{Maze, Cell, Coords}, mazer::MazeStep};
 
 #[derive(Debug, PartialEq, Eq)]
 struct DijkstraNode {
@@ -23,6 +23,17 @@ impl PartialOrd for DijkstraNode {
     }
 }
 
+pub const PSEUDOCODE: &[&str] = &[
+    "dist[start] = 0",
+    "push start into the min-heap and mark it visited",
+    "while the heap is not empty",
+    "    pop the cell with the smallest distance",
+    "    if it is the exit: walk the predecessors back to the start, marking the path",
+    "    for each open neighbour",
+    "        if dist[cell] + weight < dist[neighbour]",
+    "            update its distance and predecessor, push it, mark it visited",
+];
+
 // Function to find the shortest path through the maze using Dijkstra's algorithm
 pub fn dijkstra(
     maze: &Maze,
@@ -43,6 +54,7 @@ pub fn dijkstra(
     steps.push_front(MazeStep {
         coords: start,
         cell_type: Cell::Visited,
+        line: 1,
     });
 
     while let Some(DijkstraNode { coords, distance: dist }) = min_heap.pop() {
@@ -55,6 +67,7 @@ pub fn dijkstra(
                 steps.push_front(MazeStep {
                     coords: current,
                     cell_type: Cell::Path,
+                    line: 4,
                 });
                 maze_path.push((current.y, current.x));
                 current = path[current.y][current.x];
@@ -63,6 +76,7 @@ pub fn dijkstra(
             steps.push_front(MazeStep {
                 coords: start,
                 cell_type: Cell::Path,
+                line: 4,
             });
             maze_path.push((start.y, start.x));
             let path_vec: Vec<_> = path_coords.into_iter().collect();
@@ -83,6 +97,7 @@ pub fn dijkstra(
                     steps.push_front(MazeStep {
                         coords: new_coords,
                         cell_type: Cell::Visited,
+                        line: 7,
                     });
                     min_heap.push(DijkstraNode {
                         coords: new_coords,
@@ -0,0 +1,198 @@
+use yew::prelude::*;
+use yew_hooks::use_interval;
+
+use algovis::config::sort_config::SortConfigValues;
+use algovis::helpers::MAX_REFRESH_RATE;
+use algovis::services::complexity::{log_log_slope, Metric, Reference, Sweep, SWEEP_SIZES};
+
+use crate::components::complexity_page::complexity_chart::{series_color, ComplexityChart};
+use crate::components::ui::the_button::TheButton;
+
+fn all_algorithms(config: &SortConfigValues) -> Vec<String> {
+    config.algorithm_options().iter().map(|option| option.value.clone()).collect()
+}
+
+fn all_distributions(config: &SortConfigValues) -> Vec<String> {
+    config.distribution_options.iter().map(|option| option.value.clone()).collect()
+}
+
+// Adds or removes `value`, kept in the order of the options so series colors stay put
+fn toggle(selected: &[String], value: String, options: Vec<String>) -> Vec<String> {
+    let mut selected = selected.to_vec();
+    if selected.contains(&value) {
+        selected.retain(|name| *name != value);
+    } else {
+        selected.push(value);
+    }
+    options.into_iter().filter(|name| selected.contains(name)).collect()
+}
+
+#[function_component(Complexity)]
+pub fn complexity() -> Html {
+    let config = use_state(SortConfigValues::new);
+    let algorithms = use_state(|| all_algorithms(&config));
+    let distributions = use_state(|| vec![config.distribution.clone()]);
+    let metric = use_state(|| Metric::Comparisons);
+    let sweep: UseStateHandle<Sweep> = {
+        let config = (*config).clone();
+        let algorithms = (*algorithms).clone();
+        let distributions = (*distributions).clone();
+        use_state(move || Sweep::new(&config, &algorithms, &distributions))
+    };
+
+    {
+        let sweep = sweep.clone();
+        let tick_time = if sweep.is_done() { 0 } else { MAX_REFRESH_RATE as u32 };
+
+        use_interval(
+            move || {
+                let mut sweep_value = (*sweep).clone();
+                sweep_value.tick();
+                sweep.set(sweep_value);
+            },
+            tick_time,
+        );
+    }
+
+    let toggle_distribution = {
+        let config = config.clone();
+        let algorithms = algorithms.clone();
+        let distributions = distributions.clone();
+        let sweep = sweep.clone();
+        Callback::from(move |distribution_name: String| {
+            let selected = toggle(&distributions, distribution_name, all_distributions(&config));
+            sweep.set(Sweep::new(&config, &algorithms, &selected));
+            distributions.set(selected);
+        })
+    };
+
+    let toggle_algorithm = {
+        let config = config.clone();
+        let algorithms = algorithms.clone();
+        let distributions = distributions.clone();
+        let sweep = sweep.clone();
+        Callback::from(move |algorithm_name: String| {
+            let selected = toggle(&algorithms, algorithm_name, all_algorithms(&config));
+            sweep.set(Sweep::new(&config, &selected, &distributions));
+            algorithms.set(selected);
+        })
+    };
+
+    let distribution_buttons = config
+        .distribution_options
+        .iter()
+        .map(|option| {
+            let onclick = {
+                let toggle_distribution = toggle_distribution.clone();
+                let value = option.value.clone();
+                Callback::from(move |_| toggle_distribution.emit(value.clone()))
+            };
+            html! {
+                <TheButton class="!w-auto" active={distributions.contains(&option.value)} onclick={onclick}>
+                    {option.label.clone()}
+                </TheButton>
+            }
+        })
+        .collect::<Html>();
+
+    let algorithm_buttons = config
+        .algorithm_options()
+        .iter()
+        .map(|option| {
+            let onclick = {
+                let toggle_algorithm = toggle_algorithm.clone();
+                let value = option.value.clone();
+                Callback::from(move |_| toggle_algorithm.emit(value.clone()))
+            };
+            html! {
+                <TheButton class="!w-auto" active={algorithms.contains(&option.value)} onclick={onclick}>
+                    {option.label.clone()}
+                </TheButton>
+            }
+        })
+        .collect::<Html>();
+
+    let metric_buttons = Metric::ALL
+        .iter()
+        .map(|&value| {
+            let onclick = {
+                let metric = metric.clone();
+                Callback::from(move |_| metric.set(value))
+            };
+            html! {
+                <TheButton active={*metric == value} onclick={onclick}>
+                    {value.label()}
+                </TheButton>
+            }
+        })
+        .collect::<Html>();
+
+    let (done, total) = sweep.progress();
+    let progress = if sweep.is_done() {
+        format!("Measured {} sizes from {} to {}", SWEEP_SIZES.len(), SWEEP_SIZES[0], SWEEP_SIZES[SWEEP_SIZES.len() - 1])
+    } else {
+        format!("Measuring... {}/{}", done, total)
+    };
+
+    let rows = sweep
+        .series
+        .iter()
+        .enumerate()
+        .map(|(idx, series)| {
+            let points = series.points(*metric);
+            let slope = log_log_slope(&points).map_or("-".to_string(), |slope| format!("{:.2}", slope));
+            let closest = Reference::closest(&points).map_or("-", |reference| reference.label());
+            let largest = series.measurements.last().map_or("-".to_string(), |(n, _)| {
+                if series.is_truncated {
+                    format!("{} (too many steps beyond)", n)
+                } else {
+                    n.to_string()
+                }
+            });
+            html! {
+                <tr>
+                    <td class="pr-4">
+                        <span class="inline-block w-3 h-3 mr-2 rounded-full" style={format!("background: {}", series_color(idx, sweep.series.len()))} />
+                        {series.label.clone()}
+                    </td>
+                    <td class="pr-4">{slope}</td>
+                    <td class="pr-4">{closest}</td>
+                    <td>{largest}</td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="w-full flex flex-col-reverse md:flex-row justify-center items-start gap-6 md:mt-[100px]">
+            <div class="flex flex-col gap-3 p-5 border-2 border-accent rounded-lg w-full max-w-[320px]">
+                <div class="flex flex-wrap gap-2">
+                    {distribution_buttons}
+                </div>
+                <div class="flex flex-col gap-2">
+                    {metric_buttons}
+                </div>
+                <div class="flex flex-wrap gap-2">
+                    {algorithm_buttons}
+                </div>
+            </div>
+            <div class="w-full flex flex-col gap-3">
+                <div>{progress}</div>
+                <ComplexityChart series={sweep.series.clone()} metric={*metric} />
+                <table class="text-sm text-left">
+                    <thead>
+                        <tr>
+                            <th class="pr-4">{"Algorithm"}</th>
+                            <th class="pr-4">{"Measured exponent"}</th>
+                            <th class="pr-4">{"Closest curve"}</th>
+                            <th>{"Largest n"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {rows}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
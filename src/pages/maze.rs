@@ -1,15 +1,19 @@
 use yew::prelude::*;
 use yew_hooks::use_interval;
 
-use crate::components::maze_page::maze_config::{MazeConfig, MazeConfigValues};
+use algovis::config::maze_config::MazeConfigValues;
+use algovis::services::playable::{PlaybackCommand, Playable};
+use algovis::services::trace::{from_json, to_json, MazeTrace, TraceFormat};
+use algovis::services::{maze_generator::{Cell, Coords}, mazer::Mazer};
+
+use crate::components::maze_page::maze_config::MazeConfig;
+use crate::components::ui::the_pseudocode::ThePseudocode;
 use crate::components::ui::the_slider::TheSlider;
-use crate::services::playable::Playable;
-use crate::{
-    components::{
-        maze_page::maze_view_canvas::{Coords, MazeItem, MazeViewCanvas},
-        ui::the_button::TheButton,
-    },
-    services::{maze_generator::Cell, mazer::Mazer},
+use crate::components::ui::the_step_controls::TheStepControls;
+use crate::components::ui::the_trace_controls::TheTraceControls;
+use crate::components::{
+    maze_page::maze_view_canvas::{MazeItem, MazeViewCanvas},
+    ui::the_button::TheButton,
 };
 
 #[function_component(Maze)]
@@ -84,7 +88,49 @@ pub fn maze() -> Html {
             tick_time,
         );
     }
-    let is_playing = (*mazer).is_playing;
+    let is_playing = mazer.is_playing;
+
+    let run_command = {
+        let mazer = mazer.clone();
+        let config_value = (*config).clone();
+        Callback::from(move |command: PlaybackCommand| {
+            let mut mazer_value = (*mazer).clone();
+            mazer_value.run_command(command, &config_value);
+            mazer.set(mazer_value);
+        })
+    };
+
+    let export_trace = {
+        let mazer = mazer.clone();
+        let config_value = (*config).clone();
+        Callback::from(move |format: TraceFormat| {
+            let trace = mazer.to_trace(&config_value);
+            match format {
+                TraceFormat::Json => to_json(&trace),
+                TraceFormat::Csv => trace.to_csv(),
+            }
+        })
+    };
+
+    let import_trace = {
+        let mazer = mazer.clone();
+        let config = config.clone();
+        Callback::from(move |json: String| {
+            let trace: MazeTrace = match from_json(&json) {
+                Ok(trace) => trace,
+                Err(message) => return Some(message),
+            };
+            let config_value = trace.config.clone();
+            match Mazer::from_trace(trace) {
+                Ok(mazer_value) => {
+                    mazer.set(mazer_value);
+                    config.set(config_value);
+                    None
+                }
+                Err(message) => Some(message),
+            }
+        })
+    };
 
     let change_current_step = {
         let mazer = mazer.clone();
@@ -122,6 +168,7 @@ pub fn maze() -> Html {
                          }
                      }
                     </TheButton>
+                    <TheTraceControls on_export={export_trace} on_import={import_trace} file_name="maze-trace" />
                 </div>
             </div>
             <div>
@@ -132,9 +179,11 @@ pub fn maze() -> Html {
                 />
                 <TheSlider
                     max={mazer.get_steps_len_string()}
-                    value={(*mazer).active_step}
+                    value={mazer.active_step}
                     set_value={change_current_step}
                 />
+                <TheStepControls on_command={run_command} />
+                <ThePseudocode lines={mazer.pseudocode.to_vec()} active_line={mazer.get_active_line()} />
             </div>
         </div>
     }
@@ -1,3 +1,4 @@
 pub mod sort;
 pub mod about;
 pub mod maze;
+pub mod complexity;
@@ -1,31 +1,67 @@
 use yew::prelude::*;
 use yew_hooks::use_interval;
 
-use crate::components::sorting_page::sorting_config::{SortConfigValues, SortingConfig};
-use crate::components::sorting_page::sorting_graph_canvas::SortingGraphCanvas;
+use algovis::config::sort_config::SortConfigValues;
+use algovis::helpers::random_seed;
+use algovis::services::any_sorter::AnySorter;
+use algovis::services::playable::{PlaybackCommand, Playable};
+use algovis::services::sonification::tone_for_step;
+use algovis::services::sorter::SortStats;
+use algovis::services::trace::TraceFormat;
+
+use crate::components::sorting_page::sorting_config::SortingConfig;
+use crate::components::sorting_page::sorting_graph_canvas::{Renderer, SortingGraphCanvas};
+use crate::components::sorting_page::sorting_race::SortingRace;
+use crate::components::sorting_page::synth::Synth;
 use crate::components::ui::the_button::TheButton;
+use crate::components::ui::the_pseudocode::ThePseudocode;
 use crate::components::ui::the_slider::TheSlider;
-use crate::services::playable::Playable;
-use crate::services::sorter::Sorter;
+use crate::components::ui::the_step_controls::TheStepControls;
+use crate::components::ui::the_trace_controls::TheTraceControls;
 
 #[function_component(Sort)]
 pub fn sort() -> Html {
-    let config = use_state(|| SortConfigValues::new());
+    let config = use_state(SortConfigValues::new);
     let change_config = {
         let config = config.clone();
         Callback::from(move |value: SortConfigValues| config.set(value))
     };
 
-    let sorter: UseStateHandle<Sorter> = use_state(|| Sorter::new(&config));
+    let sorter: UseStateHandle<AnySorter> = use_state(|| AnySorter::new(&config));
+    let is_race_mode = use_state(|| false);
+    let is_sound_on = use_state(|| false);
+    let synth = use_mut_ref(|| None::<Synth>);
+
+    let set_race_mode = |value: bool| {
+        let is_race_mode = is_race_mode.clone();
+        let sorter = sorter.clone();
+        Callback::from(move |_| {
+            let mut sorter_value = (*sorter).clone();
+            sorter_value.stop();
+            sorter.set(sorter_value);
+            is_race_mode.set(value);
+        })
+    };
 
     {
         let sorter = sorter.clone();
         let tick_time = sorter.tick_time();
+        let is_sound_on = *is_sound_on;
+        let synth = synth.clone();
 
         use_interval(
             move || {
                 let mut sorter_value = (*sorter).clone();
+                let previous_step = sorter_value.get_active_step();
                 sorter_value.tick();
+                // A tick may play several steps, only the last one that moved an item is heard
+                let step = is_sound_on.then(|| sorter_value.last_move(previous_step, sorter_value.get_active_step()));
+                if let Some(step) = step.flatten() {
+                    let tone = tone_for_step(&step, &sorter_value.magnitudes(), sorter_value.value_range(), tick_time);
+                    if let (Some(tone), Some(synth)) = (tone, synth.borrow().as_ref()) {
+                        synth.play(tone).ok();
+                    }
+                }
                 sorter.set(sorter_value);
             },
             tick_time,
@@ -53,16 +89,63 @@ pub fn sort() -> Html {
         })
     };
 
+    let toggle_sound = {
+        let is_sound_on = is_sound_on.clone();
+        let synth = synth.clone();
+        Callback::from(move |_| {
+            // Audio only starts from a click, so the context is created here
+            if synth.borrow().is_none() {
+                *synth.borrow_mut() = Synth::new().ok();
+            }
+            is_sound_on.set(!*is_sound_on);
+        })
+    };
+
+    // Every press gives new data, the seed it rolls stays in the form to replay it with
     let handle_generate = {
         let sorter = sorter.clone();
-        let config = (*config).clone();
+        let config = config.clone();
         Callback::from(move |_| {
+            let mut config_value = (*config).clone();
+            config_value.seed = random_seed();
+            let mut sorter_value = (*sorter).clone();
+            sorter_value.generate(&config_value);
+            sorter.set(sorter_value);
+            config.set(config_value);
+        })
+    };
+
+    let run_command = {
+        let sorter = sorter.clone();
+        let config = config.clone();
+        Callback::from(move |command: PlaybackCommand| {
             let mut sorter_value = (*sorter).clone();
-            sorter_value.generate(&config);
+            sorter_value.run_command(command, &config);
             sorter.set(sorter_value);
         })
     };
 
+    let export_trace = {
+        let sorter = sorter.clone();
+        let config = config.clone();
+        Callback::from(move |format: TraceFormat| sorter.export_trace(&config, format))
+    };
+
+    let import_trace = {
+        let sorter = sorter.clone();
+        let config = config.clone();
+        Callback::from(move |json: String| {
+            match AnySorter::from_trace_json(&json) {
+                Ok((sorter_value, config_value)) => {
+                    sorter.set(sorter_value);
+                    config.set(config_value);
+                    None
+                }
+                Err(message) => Some(message),
+            }
+        })
+    };
+
     let change_current_step = {
         let sorter = sorter.clone();
         Callback::from(move |value: u32| {
@@ -75,11 +158,39 @@ pub fn sort() -> Html {
     let steps_info = {
         let steps_total = format!("Steps total: {}", sorter.get_steps_len_string());
         let active_step_index = format!("Active step: {}", sorter.get_active_step_string());
-        let active_step = sorter.get_active_step_item().to_string();
+        let active_step = sorter.active_step_description();
+        let SortStats { comparisons, swaps, writes, peak_aux_memory, .. } = sorter.stats();
+        let active_stats = sorter.active_stats();
         html! {
             <>
-                <div>{steps_total}</div>
+                <div class="flex flex-wrap gap-x-4">
+                    <div>{steps_total}</div>
+                    <div>{format!("Comparisons: {}/{}", active_stats.comparisons, comparisons)}</div>
+                    <div>{format!("Swaps: {}/{}", active_stats.swaps, swaps)}</div>
+                    <div>{format!("Writes: {}/{}", active_stats.writes, writes)}</div>
+                    <div>{format!("Peak aux memory: {}/{}", active_stats.peak_aux_memory, peak_aux_memory)}</div>
+                </div>
+                {
+                    match sorter.fallback() {
+                        Some(fallback) => html! { <div class="text-amber-500">{fallback}</div> },
+                        None => html! {},
+                    }
+                }
                 <div>{active_step_index}{" "}{active_step}</div>
+                {
+                    if config.is_stability_demo() {
+                        html! { <div>{format!("Equal keys left out of original order: {}", sorter.unstable_count())}</div> }
+                    } else {
+                        html! {}
+                    }
+                }
+                {
+                    if let Some(position) = sorter.active_digit() {
+                        html! { <div>{format!("Digit position: {}", position)}</div> }
+                    } else {
+                        html! {}
+                    }
+                }
             </>
         }
     };
@@ -87,40 +198,69 @@ pub fn sort() -> Html {
     html! {
         <div class="w-full flex flex-col-reverse md:flex-row justify-center items-center gap-6 md:mt-[100px]">
             <div class="flex flex-col justify-between gap-3 p-5 border-2 border-accent rounded-lg h-full w-full max-w-[320px]">
-                <SortingConfig value={(*config).clone()} on_change={change_config} />
-                <div class="flex flex-col gap-2 my-5">
-                    <TheButton onclick={handle_generate}>
-                        {"Generate"}
+                <div class="flex gap-2">
+                    <TheButton active={!*is_race_mode} onclick={set_race_mode(false)}>
+                        {"Single"}
                     </TheButton>
-                    {
-                        if (*sorter).is_playing {
-                            html! {
-                                <TheButton onclick={handle_pause}>
-                                    {"Pause"}
-                                </TheButton>
-                            }
-                        } else {
-                            html! {
-                                <TheButton onclick={handle_play}>
-                                    {"Play"}
-                                </TheButton>
+                    <TheButton active={*is_race_mode} onclick={set_race_mode(true)}>
+                        {"Race"}
+                    </TheButton>
+                </div>
+                <SortingConfig value={(*config).clone()} on_change={change_config.clone()} />
+                if !*is_race_mode {
+                    <div class="flex flex-col gap-2 my-5">
+                        <TheButton onclick={handle_generate}>
+                            {"Generate"}
+                        </TheButton>
+                        {
+                            if sorter.is_playing() {
+                                html! {
+                                    <TheButton onclick={handle_pause}>
+                                        {"Pause"}
+                                    </TheButton>
+                                }
+                            } else {
+                                html! {
+                                    <TheButton onclick={handle_play}>
+                                        {"Play"}
+                                    </TheButton>
+                                }
                             }
                         }
-                    }
-                </div>
-            </div>
-            <div class="w-full">
-                {steps_info}
-                <SortingGraphCanvas 
-                    data={(*sorter).data.clone()} 
-                    active_step_item={(*sorter).get_active_step_item()} 
-                />
-                <TheSlider 
-                    max={sorter.get_steps_len_string()} 
-                    value={(*sorter).active_step} 
-                    set_value={change_current_step} 
-                />
+                        <TheButton active={*is_sound_on} onclick={toggle_sound}>
+                            {if *is_sound_on { "Sound on" } else { "Sound off" }}
+                        </TheButton>
+                        <TheTraceControls on_export={export_trace} on_import={import_trace} file_name="sort-trace" />
+                    </div>
+                }
             </div>
+            if *is_race_mode {
+                <SortingRace config={(*config).clone()} on_change={change_config} />
+            } else {
+                <div class="w-full">
+                    {steps_info}
+                    <SortingGraphCanvas 
+                        data={sorter.magnitudes()} 
+                        value_range={sorter.value_range()}
+                        labels={sorter.labels()}
+                        aux={sorter.aux()}
+                        runs={sorter.active_runs()}
+                        tags={if config.is_stability_demo() { sorter.tags() } else { vec![] }}
+                        unstable_pairs={if config.is_stability_demo() { sorter.unstable_pairs() } else { vec![] }}
+                        active_step_item={(*sorter).get_active_step_item()} 
+                        renderer={Renderer::from_string(&config.renderer).unwrap_or(Renderer::Bars)}
+                        displacements={sorter.displacements()}
+                        annotations={sorter.annotations()}
+                    />
+                    <TheSlider 
+                        max={sorter.get_final_step().to_string()} 
+                        value={sorter.get_active_step()} 
+                        set_value={change_current_step} 
+                    />
+                    <TheStepControls on_command={run_command} />
+                    <ThePseudocode lines={sorter.pseudocode()} active_line={sorter.get_active_line()} />
+                </div>
+            }
         </div>
     }
 }
@@ -1,4 +1,4 @@
-use crate::pages::{sort::Sort, about::About, maze::Maze};
+use crate::pages::{sort::Sort, about::About, maze::Maze, complexity::Complexity};
 use yew_router::prelude::*;
 use yew::prelude::*;
 
@@ -10,6 +10,8 @@ pub enum Route {
     Sort,
     #[at("/maze")]
     Maze,
+    #[at("/complexity")]
+    Complexity,
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -33,6 +35,7 @@ pub fn switch(routes: Route) -> Html {
         Route::Home => html! { <About /> },
         Route::Sort => html! { <Sort /> },
         Route::Maze => html! { <Maze /> },
+        Route::Complexity => html! { <Complexity /> },
         Route::NotFound => html! { <h1>{ "404" }</h1> },
     }
 }
@@ -0,0 +1,269 @@
+use serde::Deserialize;
+
+use crate::config::sort_config::SortConfigValues;
+
+use super::playable::Playable;
+use super::sort_item::{ItemKind, Record, SortItem, TotalFloat, Word};
+use super::sorter::{Annotations, SortStats, SortType, Sorter};
+use super::trace::{from_json, to_json, TraceFormat};
+
+/// A sorter over whichever kind of items the config asks for
+///
+/// Pages keep one of these, so their state keeps its type when the kind of
+/// items changes. Playback reports bar heights, see `SortItem::magnitude`.
+#[derive(Clone, PartialEq)]
+pub enum AnySorter {
+    Integers(Sorter<i32>),
+    Floats(Sorter<TotalFloat>),
+    Words(Sorter<Word>),
+    Records(Sorter<Record>),
+}
+
+macro_rules! with_sorter {
+    ($any:expr, $sorter:ident => $body:expr) => {
+        match $any {
+            AnySorter::Integers($sorter) => $body,
+            AnySorter::Floats($sorter) => $body,
+            AnySorter::Words($sorter) => $body,
+            AnySorter::Records($sorter) => $body,
+        }
+    };
+}
+
+// Read ahead of the whole trace, the config tells which items the trace holds
+#[derive(Deserialize)]
+struct TraceConfig {
+    config: SortConfigValues,
+}
+
+impl AnySorter {
+    pub fn new(sort_config: &SortConfigValues) -> AnySorter {
+        match sort_config.item_kind() {
+            ItemKind::Integers => AnySorter::Integers(Sorter::new(sort_config)),
+            ItemKind::Floats => AnySorter::Floats(Sorter::new(sort_config)),
+            ItemKind::Words => AnySorter::Words(Sorter::new(sort_config)),
+            ItemKind::Records => AnySorter::Records(Sorter::new(sort_config)),
+        }
+    }
+
+    /// Sorter replaying a JSON trace, along with the config the trace was recorded with
+    pub fn from_trace_json(json: &str) -> Result<(AnySorter, SortConfigValues), String> {
+        let TraceConfig { config } = from_json(json)?;
+        let sorter = match config.item_kind() {
+            ItemKind::Integers => AnySorter::Integers(Sorter::from_trace(from_json(json)?)?),
+            ItemKind::Floats => AnySorter::Floats(Sorter::from_trace(from_json(json)?)?),
+            ItemKind::Words => AnySorter::Words(Sorter::from_trace(from_json(json)?)?),
+            ItemKind::Records => AnySorter::Records(Sorter::from_trace(from_json(json)?)?),
+        };
+        Ok((sorter, config))
+    }
+
+    pub fn export_trace(&self, sort_config: &SortConfigValues, format: TraceFormat) -> String {
+        // The items decide how the trace is read back, whatever the form shows by now
+        let mut sort_config = sort_config.clone();
+        sort_config.item_kind = self.item_kind().as_name().to_string();
+        with_sorter!(self, sorter => {
+            let trace = sorter.to_trace(&sort_config);
+            match format {
+                TraceFormat::Json => to_json(&trace),
+                TraceFormat::Csv => trace.to_csv(),
+            }
+        })
+    }
+
+    pub fn item_kind(&self) -> ItemKind {
+        match self {
+            AnySorter::Integers(_) => ItemKind::Integers,
+            AnySorter::Floats(_) => ItemKind::Floats,
+            AnySorter::Words(_) => ItemKind::Words,
+            AnySorter::Records(_) => ItemKind::Records,
+        }
+    }
+
+    /// New items for the config, sorted right away
+    pub fn generate(&mut self, sort_config: &SortConfigValues) {
+        if self.item_kind() == sort_config.item_kind() {
+            with_sorter!(self, sorter => sorter.generate(sort_config))
+        } else {
+            *self = AnySorter::new(sort_config);
+        }
+    }
+
+    pub fn stats(&self) -> SortStats {
+        with_sorter!(self, sorter => sorter.stats)
+    }
+
+    pub fn active_stats(&self) -> SortStats {
+        with_sorter!(self, sorter => sorter.active_stats)
+    }
+
+    pub fn active_digit(&self) -> Option<u32> {
+        with_sorter!(self, sorter => sorter.active_digit)
+    }
+
+    pub fn active_runs(&self) -> Vec<(usize, usize)> {
+        with_sorter!(self, sorter => sorter.active_runs.clone())
+    }
+
+    pub fn aux(&self) -> Vec<usize> {
+        with_sorter!(self, sorter => sorter.aux.clone())
+    }
+
+    pub fn tags(&self) -> Vec<usize> {
+        with_sorter!(self, sorter => sorter.tags.clone())
+    }
+
+    pub fn pseudocode(&self) -> Vec<&'static str> {
+        with_sorter!(self, sorter => sorter.pseudocode.clone())
+    }
+
+    pub fn fallback(&self) -> Option<String> {
+        with_sorter!(self, sorter => sorter.fallback.clone())
+    }
+
+    pub fn get_active_line(&self) -> Option<usize> {
+        with_sorter!(self, sorter => sorter.get_active_line())
+    }
+
+    pub fn unstable_pairs(&self) -> Vec<(usize, usize)> {
+        with_sorter!(self, sorter => sorter.unstable_pairs())
+    }
+
+    pub fn unstable_count(&self) -> usize {
+        with_sorter!(self, sorter => sorter.unstable_count())
+    }
+
+    pub fn annotations(&self) -> Annotations {
+        with_sorter!(self, sorter => sorter.active_annotations.clone())
+    }
+
+    pub fn displacements(&self) -> Vec<usize> {
+        with_sorter!(self, sorter => sorter.displacements())
+    }
+
+    pub fn value_range(&self) -> (f64, f64) {
+        with_sorter!(self, sorter => sorter.value_range())
+    }
+
+    pub fn magnitudes(&self) -> Vec<f64> {
+        with_sorter!(self, sorter => sorter.magnitudes())
+    }
+
+    pub fn labels(&self) -> Vec<String> {
+        with_sorter!(self, sorter => sorter.labels())
+    }
+
+    pub fn is_sorted(&self) -> bool {
+        with_sorter!(self, sorter => sorter.is_sorted())
+    }
+
+    pub fn last_move(&self, from: u32, to: u32) -> Option<SortType<f64>> {
+        with_sorter!(self, sorter => sorter.last_move(from, to).map(|step| step.map(|item| item.magnitude())))
+    }
+
+    /// The step shown last with its items written out, unlike `get_active_step_item`
+    pub fn active_step_description(&self) -> String {
+        with_sorter!(self, sorter => sorter.get_active_step_item().to_string())
+    }
+}
+
+impl Playable<SortConfigValues, SortType<f64>, Vec<f64>> for AnySorter {
+    fn reset(&mut self, sort_config: &SortConfigValues) {
+        with_sorter!(self, sorter => sorter.reset(sort_config))
+    }
+
+    fn play(&mut self, sort_config: &SortConfigValues) {
+        with_sorter!(self, sorter => sorter.play(sort_config))
+    }
+
+    fn stop(&mut self) {
+        with_sorter!(self, sorter => sorter.stop())
+    }
+
+    fn tick(&mut self) {
+        with_sorter!(self, sorter => sorter.tick())
+    }
+
+    fn set_step(&mut self, step: u32) {
+        with_sorter!(self, sorter => sorter.set_step(step))
+    }
+
+    fn get_active_step_string(&self) -> String {
+        with_sorter!(self, sorter => sorter.get_active_step_string())
+    }
+
+    fn tick_time(&self) -> u32 {
+        with_sorter!(self, sorter => sorter.tick_time())
+    }
+
+    fn get_output_by_step(&mut self, step: u32) -> Vec<f64> {
+        with_sorter!(self, sorter => sorter.get_output_by_step(step).iter().map(SortItem::magnitude).collect())
+    }
+
+    fn get_steps_len_string(&self) -> String {
+        with_sorter!(self, sorter => sorter.get_steps_len_string())
+    }
+
+    fn get_active_step_item(&self) -> SortType<f64> {
+        with_sorter!(self, sorter => sorter.get_active_step_item().map(|item| item.magnitude()))
+    }
+
+    fn calculate_time(&mut self, sort_config: &SortConfigValues) {
+        with_sorter!(self, sorter => Playable::calculate_time(sorter, sort_config))
+    }
+
+    fn is_playing(&self) -> bool {
+        with_sorter!(self, sorter => sorter.is_playing)
+    }
+
+    fn get_active_step(&self) -> u32 {
+        with_sorter!(self, sorter => sorter.active_step)
+    }
+
+    fn get_final_step(&self) -> u32 {
+        with_sorter!(self, sorter => sorter.get_final_step())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_kind_of_item_sorts_and_round_trips() {
+        let mut config = SortConfigValues::new();
+        config.items_count = 40;
+        for (kind, algorithm) in [
+            ("floats", "quick_sort"),
+            ("words", "counting_sort"),
+            ("records", "radix_sort_lsd"),
+            ("records", "tim_sort"),
+        ] {
+            config.item_kind = kind.to_string();
+            config.current_algorithm_name = algorithm.to_string();
+            let mut sorter = AnySorter::new(&config);
+            assert_eq!(sorter.item_kind().as_name(), kind);
+            let final_step = sorter.get_final_step();
+            sorter.set_step(final_step);
+            assert!(sorter.is_sorted(), "{} with {}", kind, algorithm);
+            assert!(sorter.magnitudes().windows(2).all(|pair| pair[0] <= pair[1]), "{} with {}", kind, algorithm);
+
+            let (imported, _) = AnySorter::from_trace_json(&sorter.export_trace(&config, TraceFormat::Json)).unwrap();
+            assert_eq!(imported.item_kind(), sorter.item_kind());
+            assert_eq!(imported.get_final_step(), final_step);
+        }
+
+        // Words have no integer key, so counting sort hands over to merge sort
+        config.item_kind = "words".to_string();
+        config.current_algorithm_name = "counting_sort".to_string();
+        assert!(!config.algorithm_options().iter().any(|option| option.value == "counting_sort"));
+        assert_eq!(AnySorter::new(&config).pseudocode(), crate::sorting_algorithms::merge_sort::PSEUDOCODE.to_vec());
+
+        config.input_mode = "custom".to_string();
+        config.custom_input = "pear apple fig apple".to_string();
+        let mut sorter = AnySorter::new(&config);
+        sorter.set_step(sorter.get_final_step());
+        assert_eq!(sorter.labels(), ["apple", "apple", "fig", "pear"]);
+        assert!(sorter.unstable_pairs().is_empty());
+    }
+}
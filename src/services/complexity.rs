@@ -0,0 +1,286 @@
+use crate::config::sort_config::SortConfigValues;
+use crate::helpers::{get_new_generation, Distribution};
+
+use super::sorter::{measure, SortStats};
+
+/// Input sizes of a sweep, doubling from 8 up to 4096
+pub const SWEEP_SIZES: [usize; 10] = [8, 16, 32, 64, 128, 256, 512, 1024, 2048, 4096];
+// A measured run keeps all of its steps in memory for a moment, so sizes
+// expected to record more than this are left out
+const STEP_BUDGET: usize = 2_000_000;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Metric {
+    Comparisons,
+    Swaps,
+    Writes,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 3] = [Metric::Comparisons, Metric::Swaps, Metric::Writes];
+
+    pub fn from_string(s: &str) -> Result<Metric, &'static str> {
+        match s {
+            "comparisons" => Ok(Metric::Comparisons),
+            "swaps" => Ok(Metric::Swaps),
+            "writes" => Ok(Metric::Writes),
+            _ => Err("Invalid variant"),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Metric::Comparisons => "Comparisons",
+            Metric::Swaps => "Swaps",
+            Metric::Writes => "Writes",
+        }
+    }
+
+    pub fn value(&self, stats: &SortStats) -> u32 {
+        match self {
+            Metric::Comparisons => stats.comparisons,
+            Metric::Swaps => stats.swaps,
+            Metric::Writes => stats.writes,
+        }
+    }
+}
+
+/// Growth curves the measurements are compared against
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Reference {
+    Linear,
+    Linearithmic,
+    Quadratic,
+}
+
+impl Reference {
+    pub const ALL: [Reference; 3] = [Reference::Linear, Reference::Linearithmic, Reference::Quadratic];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Reference::Linear => "n",
+            Reference::Linearithmic => "n log n",
+            Reference::Quadratic => "n²",
+        }
+    }
+
+    pub fn value(&self, n: f64) -> f64 {
+        match self {
+            Reference::Linear => n,
+            Reference::Linearithmic => n * n.log2(),
+            Reference::Quadratic => n * n,
+        }
+    }
+
+    /// Factor `c` that puts `c * f(n)` closest to the points on log-log axes
+    ///
+    /// With the shape fixed, the least squares fit of the log values comes down
+    /// to the mean of `log(y / f(n))`. Points at zero have no logarithm and are skipped.
+    pub fn fit(&self, points: &[(usize, u32)]) -> Option<f64> {
+        let log_ratios = log_points(points)
+            .map(|(n, y)| y - self.value(n.exp()).ln())
+            .collect::<Vec<f64>>();
+        mean(&log_ratios).map(f64::exp)
+    }
+
+    /// Mean squared distance of the points from the fitted curve on log axes
+    fn residual(&self, points: &[(usize, u32)]) -> Option<f64> {
+        let factor = self.fit(points)?.ln();
+        let errors = log_points(points)
+            .map(|(n, y)| (y - factor - self.value(n.exp()).ln()).powi(2))
+            .collect::<Vec<f64>>();
+        mean(&errors)
+    }
+
+    /// The reference curve the points follow best, none without enough points to tell
+    pub fn closest(points: &[(usize, u32)]) -> Option<Reference> {
+        if log_points(points).count() < 2 {
+            return None;
+        }
+        Reference::ALL
+            .into_iter()
+            .filter_map(|reference| reference.residual(points).map(|residual| (reference, residual)))
+            .min_by(|(_, first), (_, second)| first.total_cmp(second))
+            .map(|(reference, _)| reference)
+    }
+}
+
+// Points with a logarithm, n of 1 is skipped too as every reference is flat there
+fn log_points(points: &[(usize, u32)]) -> impl Iterator<Item = (f64, f64)> + '_ {
+    points
+        .iter()
+        .filter(|&&(n, y)| n > 1 && y > 0)
+        .map(|&(n, y)| ((n as f64).ln(), (y as f64).ln()))
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+/// Slope of the least squares line through the points on log-log axes, the measured exponent
+pub fn log_log_slope(points: &[(usize, u32)]) -> Option<f64> {
+    let points = log_points(points).collect::<Vec<(f64, f64)>>();
+    let mean_x = mean(&points.iter().map(|&(x, _)| x).collect::<Vec<f64>>())?;
+    let mean_y = mean(&points.iter().map(|&(_, y)| y).collect::<Vec<f64>>())?;
+    let covariance: f64 = points.iter().map(|&(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|&(x, _)| (x - mean_x).powi(2)).sum();
+    if variance == 0. {
+        None
+    } else {
+        Some(covariance / variance)
+    }
+}
+
+/// Measurements of one algorithm on one distribution over the sweep sizes
+#[derive(Clone, PartialEq, Debug)]
+pub struct Series {
+    pub algorithm_name: String,
+    pub distribution: Distribution,
+    pub label: String,
+    pub measurements: Vec<(usize, SortStats)>,
+    /// Set when the larger sizes were left out for recording too many steps
+    pub is_truncated: bool,
+}
+
+impl Series {
+    pub fn points(&self, metric: Metric) -> Vec<(usize, u32)> {
+        self.measurements.iter().map(|(n, stats)| (*n, metric.value(stats))).collect()
+    }
+}
+
+/// Every chosen algorithm measured on every chosen distribution over every sweep size, one run per tick
+#[derive(Clone, PartialEq, Debug)]
+pub struct Sweep {
+    pub series: Vec<Series>,
+    sort_config: SortConfigValues,
+    next_series: usize,
+    next_size: usize,
+    last_steps_count: usize,
+}
+
+impl Sweep {
+    pub fn new(sort_config: &SortConfigValues, algorithms: &[String], distributions: &[String]) -> Sweep {
+        let series = algorithms
+            .iter()
+            .flat_map(|algorithm_name| {
+                distributions.iter().map(move |distribution_name| Series {
+                    algorithm_name: algorithm_name.clone(),
+                    distribution: Distribution::from_string(distribution_name).unwrap_or(Distribution::Shuffled),
+                    label: format!(
+                        "{}, {}",
+                        sort_config.algorithm_label(algorithm_name),
+                        sort_config.distribution_label(distribution_name)
+                    ),
+                    measurements: Vec::new(),
+                    is_truncated: false,
+                })
+            })
+            .collect();
+        Self {
+            series,
+            sort_config: sort_config.clone(),
+            next_series: 0,
+            next_size: 0,
+            last_steps_count: 0,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.next_series >= self.series.len()
+    }
+
+    /// Runs measured so far out of the runs the sweep can have at most
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self.next_series * SWEEP_SIZES.len() + self.next_size;
+        (done.min(self.series.len() * SWEEP_SIZES.len()), self.series.len() * SWEEP_SIZES.len())
+    }
+
+    pub fn tick(&mut self) {
+        if self.is_done() {
+            return;
+        }
+        // Doubling n at most quadruples the steps of any algorithm here
+        if self.next_size > 0 && self.last_steps_count * 4 > STEP_BUDGET {
+            self.series[self.next_series].is_truncated = true;
+            self.next_series();
+            return;
+        }
+
+        let series = &mut self.series[self.next_series];
+        let n = SWEEP_SIZES[self.next_size];
+        let mut sort_config = self.sort_config.clone();
+        sort_config.current_algorithm_name = series.algorithm_name.clone();
+        let mut data = get_new_generation(&(n as i32), &series.distribution, sort_config.seed);
+        let (stats, steps_count) = measure(&sort_config, &mut data);
+        series.measurements.push((n, stats));
+        self.last_steps_count = steps_count;
+
+        self.next_size += 1;
+        if self.next_size == SWEEP_SIZES.len() {
+            self.next_series();
+        }
+    }
+
+    fn next_series(&mut self) {
+        self.next_series += 1;
+        self.next_size = 0;
+        self.last_steps_count = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fits_recover_the_growth_of_exact_curves() {
+        let quadratic: Vec<(usize, u32)> = SWEEP_SIZES.iter().map(|&n| (n, (3 * n * n) as u32)).collect();
+        assert!((log_log_slope(&quadratic).unwrap() - 2.).abs() < 1e-9);
+        assert!((Reference::Quadratic.fit(&quadratic).unwrap() - 3.).abs() < 1e-9);
+        assert_eq!(Reference::closest(&quadratic), Some(Reference::Quadratic));
+        assert_eq!(Reference::closest(&[(8, 0), (16, 0)]), None);
+    }
+
+    #[test]
+    fn test_sweep_tells_quadratic_from_linearithmic_sorts() {
+        let mut config = SortConfigValues::new();
+        config.seed = 5;
+        let mut sweep =
+            Sweep::new(&config, &["bubble_sort".to_string(), "merge_sort".to_string()], &["shuffled".to_string()]);
+        while !sweep.is_done() {
+            sweep.tick();
+        }
+
+        let bubble = &sweep.series[0];
+        assert!(bubble.is_truncated);
+        assert!(bubble.measurements.len() < SWEEP_SIZES.len());
+        let merge = &sweep.series[1];
+        assert!(!merge.is_truncated);
+        assert_eq!(merge.measurements.len(), SWEEP_SIZES.len());
+
+        let comparisons = |series: &Series| series.points(Metric::Comparisons);
+        assert_eq!(Reference::closest(&comparisons(bubble)), Some(Reference::Quadratic));
+        assert_eq!(Reference::closest(&comparisons(merge)), Some(Reference::Linearithmic));
+    }
+
+    #[test]
+    fn test_sweep_measures_every_distribution_apart() {
+        let config = SortConfigValues::new();
+        let mut sweep = Sweep::new(&config, &["insertion_sort".to_string()], &["sorted".to_string(), "reversed".to_string()]);
+        while !sweep.is_done() {
+            sweep.tick();
+        }
+
+        let [sorted, reversed] = &sweep.series[..] else {
+            panic!("expected a series per distribution");
+        };
+        assert_eq!(sorted.distribution, Distribution::Sorted);
+        assert_eq!(sorted.label, "Insertion Sort, Sorted");
+        assert_eq!(Reference::closest(&sorted.points(Metric::Comparisons)), Some(Reference::Linear));
+        assert_eq!(Reference::closest(&reversed.points(Metric::Comparisons)), Some(Reference::Quadratic));
+    }
+}
@@ -4,11 +4,29 @@ use std::slice::Iter;
 use rand::distributions::uniform::SampleRange;
 use rand::distributions::uniform::SampleUniform;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use crate::components::maze_page::maze_config::MazeConfigValues;
-use crate::components::maze_page::maze_view_canvas::Coords;
+use crate::config::maze_config::MazeConfigValues;
+use crate::helpers::rng_from_seed;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Coords<T: Copy> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Copy> Coords<T> {
+    pub fn from(x: T, y: T) -> Self {
+        Coords::<T> { x, y }
+    }
+
+    pub fn as_row_col(&self) -> (T, T) {
+        (self.y, self.x)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Cell {
     Visited,
     Path,
@@ -19,6 +37,7 @@ pub enum Cell {
 }
 
 impl Cell {
+    #[allow(dead_code)]
     pub fn iterator() -> Iter<'static, Cell> {
         static CELL_COLORS: [Cell; 6] = [
             Cell::Visited,
@@ -30,6 +49,7 @@ impl Cell {
         ];
         CELL_COLORS.iter()
     }
+    #[allow(dead_code)]
     pub fn as_name(&self) -> &'static str {
         match self {
             Cell::Empty => "Empty",
@@ -41,6 +61,17 @@ impl Cell {
         }
     }
 
+    /// Cell drawn by a character of a text maze, see `Maze::from_text`
+    pub fn from_char(c: char) -> Result<Cell, String> {
+        match c {
+            '#' => Ok(Cell::Wall),
+            '.' | ' ' => Ok(Cell::Empty),
+            'S' => Ok(Cell::Entry),
+            'E' => Ok(Cell::Exit),
+            _ => Err(format!("Unexpected maze character \"{}\"", c)),
+        }
+    }
+
     pub fn as_color(&self) -> &'static str {
         match self {
             Cell::Empty => "#f5f5f5",   
@@ -59,18 +90,18 @@ enum Orientation {
     Vertical,
 }
 
-fn rand_num<T, R>(range: R) -> T
+fn rand_num<T, R>(rng: &mut impl Rng, range: R) -> T
 where
     T: SampleUniform,
     R: SampleRange<T>,
 {
-    rand::thread_rng().gen_range(range)
+    rng.gen_range(range)
 }
 fn float_even(num: f32) -> f32 {
     (num / 2.0).ceil() * 2.0
 }
 
-fn generate_new_maze(width: usize, height: usize) -> Maze {
+fn generate_new_maze(width: usize, height: usize, seed: u64) -> Maze {
     let cells = vec![vec![Cell::Empty; width]; height];
     let mut maze = Maze {
         cells,
@@ -78,7 +109,7 @@ fn generate_new_maze(width: usize, height: usize) -> Maze {
         height,
     };
     maze.generate_side_walls();
-    maze.generate(1, width - 2, 1, height - 2);
+    maze.generate(&mut rng_from_seed(seed), 1, width - 2, 1, height - 2);
     maze.set_default_entry_exit();
     maze
 }
@@ -91,12 +122,33 @@ pub struct Maze {
 }
 
 impl Maze {
-    pub fn new(width: usize, height: usize) -> Maze {
-        generate_new_maze(width, height)
+    pub fn new(width: usize, height: usize, seed: u64) -> Maze {
+        generate_new_maze(width, height, seed)
+    }
+
+    /// Reads a maze drawn as text, `#` for walls, `.` for empty cells, `S` for the entry and `E` for the exit
+    pub fn from_text(text: &str) -> Result<Maze, String> {
+        let cells = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.chars().map(Cell::from_char).collect::<Result<Vec<Cell>, String>>())
+            .collect::<Result<Vec<Vec<Cell>>, String>>()?;
+        let height = cells.len();
+        let width = cells.first().map_or(0, |row| row.len());
+        if width == 0 || cells.iter().any(|row| row.len() != width) {
+            return Err(String::from("Every row of the maze must have the same length"));
+        }
+        for cell_type in [Cell::Entry, Cell::Exit] {
+            let count = cells.iter().flatten().filter(|&&cell| cell == cell_type).count();
+            if count != 1 {
+                return Err(format!("The maze needs exactly one {} cell, found {}", cell_type.as_name(), count));
+            }
+        }
+        Ok(Maze { cells, width, height })
     }
 
     pub fn get_weight(&self) -> u32 {
-        return 1;
+        1
     }
 
     pub fn reset(&mut self, config: &MazeConfigValues) {
@@ -104,7 +156,7 @@ impl Maze {
         self.height = config.size;
         self.cells = vec![vec![Cell::Empty; self.width]; self.height];
         self.generate_side_walls();
-        self.generate(1, self.width - 2, 1, self.height - 2);
+        self.generate(&mut rng_from_seed(config.seed), 1, self.width - 2, 1, self.height - 2);
         self.set_default_entry_exit();
     }
 
@@ -137,8 +189,15 @@ impl Maze {
         }
     }
 
-    fn generate(&mut self, start_x: usize, width: usize, start_y: usize, height: usize) {
-        let orientation = get_orientation(width, height);
+    fn generate(
+        &mut self,
+        rng: &mut impl Rng,
+        start_x: usize,
+        width: usize,
+        start_y: usize,
+        height: usize,
+    ) {
+        let orientation = get_orientation(rng, width, height);
 
         // Return when chamber is minimum size
         if orientation == Orientation::Horizontal && height <= 2
@@ -155,7 +214,7 @@ impl Maze {
             Orientation::Horizontal => {
                 // Horizontal walls on even y-coordinates
                 let range = start_y..start_y + height - 1;
-                let y = float_even(rand_num(range) as f32) as usize;
+                let y = float_even(rand_num(rng, range) as f32) as usize;
 
                 from = (start_x, y);
                 to = (end.0, y);
@@ -167,7 +226,7 @@ impl Maze {
             Orientation::Vertical => {
                 // Vertical walls on even x-coordinates
                 let range = start_x..start_x + width - 1;
-                let x = float_even(rand_num(range) as f32) as usize;
+                let x = float_even(rand_num(rng, range) as f32) as usize;
                 for y in start_y..=end.1 {
                     self.cells[y][x] = Cell::Wall;
                     wall_points.push((x, y));
@@ -188,9 +247,9 @@ impl Maze {
             .collect::<Vec<&(usize, usize)>>();
 
         // Get random point from the vec of valid passage points
-        if odd_wall_points.len() > 0 {
+        if !odd_wall_points.is_empty() {
             let p_len = 0..(odd_wall_points.len());
-            let passage = odd_wall_points[rand_num(p_len)];
+            let passage = odd_wall_points[rand_num(rng, p_len)];
 
             // // Remove the point from the wall to create a passage
             let passage_index = wall_points.iter().position(|coord| coord == passage);
@@ -204,29 +263,29 @@ impl Maze {
             // If the wall is horizontal, recurse above and below
             Orientation::Horizontal => {
                 // Top section
-                self.generate(start_x, width, start_y, from.1 - start_y);
+                self.generate(rng, start_x, width, start_y, from.1 - start_y);
                 // Bottom section
-                self.generate(start_x, width, from.1 + 1, end.1 - from.1);
+                self.generate(rng, start_x, width, from.1 + 1, end.1 - from.1);
             }
             // If the wall is vertical, recurse left and right
             Orientation::Vertical => {
                 // Left section
-                self.generate(start_x, to.0 - start_x, start_y, height);
+                self.generate(rng, start_x, to.0 - start_x, start_y, height);
                 // Bottom section
-                self.generate(to.0 + 1, end.0 - to.0, start_y, height);
+                self.generate(rng, to.0 + 1, end.0 - to.0, start_y, height);
             }
         }
     }
 
     fn get_cell(&mut self, coords: Coords<usize>) -> Cell {
-        let y = coords.y as usize;
-        let x = coords.x as usize;
+        let y = coords.y;
+        let x = coords.x;
         self.cells[y][x]
     }
 
     fn modify_cell(&mut self, coords: Coords<usize>, value: Cell) -> Cell {
-        let y = coords.y as usize;
-        let x = coords.x as usize;
+        let y = coords.y;
+        let x = coords.x;
         self.cells[y][x] = value;
         self.cells[y][x]
     }
@@ -280,13 +339,13 @@ impl Maze {
     }
 }
 
-fn get_orientation(width: usize, height: usize) -> Orientation {
+fn get_orientation(rng: &mut impl Rng, width: usize, height: usize) -> Orientation {
     if width < height {
         Orientation::Horizontal
     } else if height < width {
         Orientation::Vertical
     } else {
-        if rand::thread_rng().gen_range::<u8, RangeInclusive<u8>>(0..=1) == 0 {
+        if rng.gen_range::<u8, RangeInclusive<u8>>(0..=1) == 0 {
             Orientation::Horizontal
         } else {
             Orientation::Vertical
@@ -296,7 +355,7 @@ fn get_orientation(width: usize, height: usize) -> Orientation {
 
 #[cfg(test)]
 mod tests {
-    use crate::maze_solver_algorithms::dfs::is_path_between;
+    use crate::maze_solver_algorithms::dfs::solve_maze_by_dfs;
 
     use super::*;
 
@@ -304,7 +363,7 @@ mod tests {
     fn test_maze_generation() {
         let width = 30;
         let height = 30;
-        let maze = Maze::new(width, height);
+        let maze = Maze::new(width, height, 7);
 
         // Check that the maze has the correct dimensions
         assert_eq!(maze.cells.len(), height);
@@ -313,7 +372,35 @@ mod tests {
         }
 
         // Check that there is a path from entry to exit
-        let (_path, has_path, ..) = is_path_between(&maze, maze.entry(), maze.exit());
-        assert!(has_path);
+        let (path, _steps) = solve_maze_by_dfs(&maze, maze.entry(), maze.exit());
+        assert!(!path.is_empty());
+    }
+
+    #[test]
+    fn test_maze_generation_is_reproducible_by_seed() {
+        let mut config = MazeConfigValues::new();
+        config.seed = 12345;
+        let maze = Maze::new(config.size, config.size, config.seed);
+        assert_eq!(maze.cells, Maze::new(config.size, config.size, config.seed).cells);
+
+        let mut reset_maze = Maze::new(config.size, config.size, 1);
+        reset_maze.reset(&config);
+        assert_eq!(maze.cells, reset_maze.cells);
+
+        assert_ne!(maze.cells, Maze::new(config.size, config.size, 54321).cells);
+    }
+
+    #[test]
+    fn test_maze_from_text() {
+        let maze = Maze::from_text("#####\n#S..#\n###.#\n#E..#\n#####\n").unwrap();
+        assert_eq!((maze.width, maze.height), (5, 5));
+        assert_eq!(maze.entry(), Coords::from(1, 1));
+        assert_eq!(maze.exit(), Coords::from(1, 3));
+        let (path, _steps) = solve_maze_by_dfs(&maze, maze.entry(), maze.exit());
+        assert!(path.contains(&(3, 2)));
+
+        assert!(Maze::from_text("#S#\n#E").is_err());
+        assert!(Maze::from_text("#S#\n#.#").is_err());
+        assert!(Maze::from_text("#S?E#").is_err());
     }
 }
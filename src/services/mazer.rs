@@ -1,27 +1,42 @@
 use std::collections::VecDeque;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    components::maze_page::{maze_config::MazeConfigValues, maze_view_canvas::Coords},
+    config::maze_config::MazeConfigValues,
     helpers::{MAX_REFRESH_RATE, MS_IN_SECS},
-    maze_solver_algorithms::{dfs::solve_maze_by_dfs, dijkstra::solve_maze_by_dijkstra},
+    maze_solver_algorithms::{dfs, dfs::solve_maze_by_dfs, dijkstra, dijkstra::solve_maze_by_dijkstra},
 };
 
-use super::{maze_generator::{Cell, Maze}, playable::Playable};
+use super::{
+    maze_generator::{Cell, Coords, Maze},
+    playable::Playable,
+    timeline::{Replay, Timeline},
+    trace::{MazeTrace, TRACE_VERSION},
+};
 
 #[derive(Clone, PartialEq, Debug)]
 enum MazeAlgorithmsEnum {
     Dijkstra,
-    DFS,
+    Dfs,
 }
 
 impl MazeAlgorithmsEnum {
     fn from_string(s: String) -> Result<MazeAlgorithmsEnum, &'static str> {
         match s.as_str() {
             "dijkstra" => Ok(MazeAlgorithmsEnum::Dijkstra),
-            "dfs" => Ok(MazeAlgorithmsEnum::DFS),
+            "dfs" => Ok(MazeAlgorithmsEnum::Dfs),
             _ => Err("Invalid variant"),
         }
     }
+
+    fn pseudocode(&self) -> &'static [&'static str] {
+        match self {
+            MazeAlgorithmsEnum::Dijkstra => dijkstra::PSEUDOCODE,
+            MazeAlgorithmsEnum::Dfs => dfs::PSEUDOCODE,
+        }
+    }
 }
 
 pub type MazeSolverReturnType = (
@@ -43,25 +58,50 @@ impl MazeAlgorithm {
     }
 
     fn from(config: &MazeConfigValues) -> MazeSolverType {
-        let result = MazeAlgorithmsEnum::from_string(config.current_algorithm_name.clone());
-        match result {
-            Ok(v) => MazeAlgorithm::from_enum(v),
-            Err(_) => MazeAlgorithm::from_enum(MazeAlgorithmsEnum::Dijkstra),
-        }
+        MazeAlgorithm::from_enum(MazeAlgorithm::enum_from(config))
+    }
+
+    fn enum_from(config: &MazeConfigValues) -> MazeAlgorithmsEnum {
+        MazeAlgorithmsEnum::from_string(config.current_algorithm_name.clone())
+            .unwrap_or(MazeAlgorithmsEnum::Dijkstra)
+    }
+
+    fn pseudocode(config: &MazeConfigValues) -> &'static [&'static str] {
+        MazeAlgorithm::enum_from(config).pseudocode()
     }
 
     fn from_enum(enum_value: MazeAlgorithmsEnum) -> MazeSolverType {
         match enum_value {
             MazeAlgorithmsEnum::Dijkstra => solve_maze_by_dijkstra,
-            MazeAlgorithmsEnum::DFS => solve_maze_by_dfs,
+            MazeAlgorithmsEnum::Dfs => solve_maze_by_dfs,
         }
     }
 }
 
-#[derive(PartialEq, Copy, Clone, Debug)]
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct MazeStep {
     pub coords: Coords<usize>,
     pub cell_type: Cell,
+    /// Line of the solver pseudocode that produced the step
+    pub line: usize,
+}
+
+// Entry and exit cells keep their look whatever the solver reports for them
+impl Replay<MazeStep> for Vec<Vec<Cell>> {
+    type Undo = Cell;
+
+    fn apply(&mut self, step: &MazeStep) -> Cell {
+        let MazeStep { coords, cell_type, .. } = *step;
+        let previous = self[coords.y][coords.x];
+        if previous != Cell::Entry && previous != Cell::Exit {
+            self[coords.y][coords.x] = cell_type;
+        }
+        previous
+    }
+
+    fn revert(&mut self, step: &MazeStep, undo: &Cell) {
+        self[step.coords.y][step.coords.x] = *undo;
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -70,7 +110,9 @@ pub struct Mazer {
     pub width: usize,
     pub height: usize,
     pub path: Vec<(usize, usize)>,
-    pub steps: VecDeque<MazeStep>,
+    pub steps: Rc<VecDeque<MazeStep>>,
+    pub pseudocode: &'static [&'static str],
+    timeline: Timeline<Vec<Vec<Cell>>, MazeStep>,
     pub steps_time: f32,
     pub is_playing: bool,
     pub active_step: u32,
@@ -82,13 +124,15 @@ impl Mazer {
         let width = config.size;
         let height = config.size;
         Self {
-            steps: VecDeque::new(),
+            steps: Rc::new(VecDeque::new()),
+            pseudocode: &[],
+            timeline: Timeline::new(Vec::new(), &VecDeque::new()),
             active_step: 0,
             steps_time: 0.,
             is_playing: false,
             width,
             height,
-            maze: Maze::new(width, height),
+            maze: Maze::new(width, height, config.seed),
             path: Vec::new(),
             initial_cells: Vec::new(),
         }
@@ -114,10 +158,6 @@ impl Mazer {
         self.active_step = 0;
     }
 
-    pub fn get_final_step(&self) -> u32 {
-        self.max_steps().max(1) - 1
-    }
-
     pub fn drop_cells(&mut self) {
         self.maze.cells = self.initial_cells.clone();
     }
@@ -126,13 +166,68 @@ impl Mazer {
         self.initial_cells = self.maze.cells.clone();
         let (path, steps) =
             MazeAlgorithm::run(config, &self.maze, self.maze.entry(), self.maze.exit());
-        self.steps = steps;
+        self.load(config, path, steps);
+    }
+
+    fn load(&mut self, config: &MazeConfigValues, path: Vec<(usize, usize)>, steps: VecDeque<MazeStep>) {
+        self.timeline = Timeline::new(self.initial_cells.clone(), &steps);
+        self.steps = Rc::new(steps);
+        self.pseudocode = MazeAlgorithm::pseudocode(config);
         self.path = path;
         self.set_step(self.get_final_step());
     }
 
+    /// Mazer replaying a recorded trace instead of running the solver
+    pub fn from_trace(trace: MazeTrace) -> Result<Mazer, String> {
+        let height = trace.cells.len();
+        let width = trace.cells.first().map_or(0, |row| row.len());
+        if width == 0 || trace.cells.iter().any(|row| row.len() != width) {
+            return Err("Maze cells must form a non-empty rectangle".to_string());
+        }
+        let fits = |x: usize, y: usize| x < width && y < height;
+        if let Some(position) = trace.steps.iter().position(|step| !fits(step.coords.x, step.coords.y)) {
+            return Err(format!("Step {} lies outside the {}x{} maze", position + 1, width, height));
+        }
+        if trace.path.iter().any(|&(x, y)| !fits(x, y)) {
+            return Err(format!("Path leaves the {}x{} maze", width, height));
+        }
+
+        // Built directly, the recorded maze need not match what the config would generate
+        let mut mazer = Self {
+            steps: Rc::new(VecDeque::new()),
+            pseudocode: &[],
+            timeline: Timeline::new(Vec::new(), &VecDeque::new()),
+            active_step: 0,
+            steps_time: 0.,
+            is_playing: false,
+            width,
+            height,
+            maze: Maze { cells: trace.cells.clone(), width, height },
+            path: Vec::new(),
+            initial_cells: trace.cells,
+        };
+        mazer.load(&trace.config, trace.path, trace.steps.into_iter().rev().collect());
+        Ok(mazer)
+    }
+
+    pub fn to_trace(&self, config: &MazeConfigValues) -> MazeTrace {
+        MazeTrace {
+            version: TRACE_VERSION,
+            config: config.clone(),
+            cells: self.initial_cells.clone(),
+            steps: self.steps.iter().rev().copied().collect(),
+            path: self.path.clone(),
+        }
+    }
+
+    /// Pseudocode line of the step shown last, none before the first one
+    pub fn get_active_line(&self) -> Option<usize> {
+        let applied = (self.active_step as usize).min(self.steps.len());
+        self.steps.get(self.steps.len().checked_sub(applied)?).map(|step| step.line)
+    }
+
     fn max_steps(&self) -> u32 {
-        return self.steps.len() as u32;
+        self.steps.len() as u32
     }
 }
 
@@ -200,18 +295,8 @@ impl Playable<MazeConfigValues, MazeStep, Vec<Vec<Cell>>> for Mazer {
     }
 
     fn get_output_by_step(&mut self, step: u32) -> Vec<Vec<Cell>> {
-        let mut steps = self.steps.clone();
-        let mut data = self.initial_cells.clone();
-        for _ in 0..step {
-            if let Some(MazeStep { coords, cell_type }) = steps.pop_back() {
-                if data[coords.y][coords.x] != Cell::Entry && data[coords.y][coords.x] != Cell::Exit {
-                    data[coords.y][coords.x] = cell_type;
-                }
-            } else {
-                break; // If steps are exhausted, stop updating data
-            }
-        }
-        data
+        self.timeline.seek(&self.steps, step as usize);
+        self.timeline.state().clone()
     }
 
     fn get_steps_len_string(&self) -> String {
@@ -225,4 +310,16 @@ impl Playable<MazeConfigValues, MazeStep, Vec<Vec<Cell>>> for Mazer {
     fn calculate_time(&mut self, config: &MazeConfigValues) {
         self.steps_time = config.time_overall as f32 / self.steps.len() as f32 * MS_IN_SECS;
     }
+
+    fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    fn get_active_step(&self) -> u32 {
+        self.active_step
+    }
+
+    fn get_final_step(&self) -> u32 {
+        self.max_steps().max(1) - 1
+    }
 }
@@ -1,4 +1,13 @@
 pub mod sorter;
+pub mod any_sorter;
+pub mod sort_item;
+pub mod sort_key;
+pub mod sonification;
 pub mod mazer;
 pub mod maze_generator;
 pub mod playable;
+pub mod race;
+pub mod stability;
+pub mod timeline;
+pub mod trace;
+pub mod complexity;
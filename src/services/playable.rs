@@ -1,3 +1,26 @@
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PlaybackCommand {
+    TogglePlay,
+    StepForward,
+    StepBack,
+    JumpToStart,
+    JumpToEnd,
+}
+
+impl PlaybackCommand {
+    /// Maps a `KeyboardEvent.key` value to the command bound to it
+    pub fn from_key(key: &str) -> Result<PlaybackCommand, &'static str> {
+        match key {
+            " " => Ok(PlaybackCommand::TogglePlay),
+            "ArrowRight" => Ok(PlaybackCommand::StepForward),
+            "ArrowLeft" => Ok(PlaybackCommand::StepBack),
+            "Home" => Ok(PlaybackCommand::JumpToStart),
+            "End" => Ok(PlaybackCommand::JumpToEnd),
+            _ => Err("Invalid key"),
+        }
+    }
+}
+
 pub trait Playable<Config, StepType, Output> {
     fn reset(&mut self, config: &Config);
     fn play(&mut self, config: &Config);
@@ -10,5 +33,37 @@ pub trait Playable<Config, StepType, Output> {
     fn get_steps_len_string(&self) -> String;
     fn get_active_step_item(&self) -> StepType;
     fn calculate_time(&mut self, sort_config: &Config);
-}
+    fn is_playing(&self) -> bool;
+    fn get_active_step(&self) -> u32;
+    fn get_final_step(&self) -> u32;
 
+    /// Manual stepping pauses playback, so the step stays where it was put
+    fn run_command(&mut self, command: PlaybackCommand, config: &Config) {
+        match command {
+            PlaybackCommand::TogglePlay => {
+                if self.is_playing() {
+                    self.stop();
+                } else {
+                    self.play(config);
+                }
+            }
+            PlaybackCommand::StepForward => {
+                self.stop();
+                let step = (self.get_active_step() + 1).min(self.get_final_step());
+                self.set_step(step);
+            }
+            PlaybackCommand::StepBack => {
+                self.stop();
+                self.set_step(self.get_active_step().saturating_sub(1));
+            }
+            PlaybackCommand::JumpToStart => {
+                self.stop();
+                self.set_step(0);
+            }
+            PlaybackCommand::JumpToEnd => {
+                self.stop();
+                self.set_step(self.get_final_step());
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use crate::config::sort_config::SortConfigValues;
+use crate::helpers::{MAX_REFRESH_RATE, MS_IN_SECS};
+
+use super::any_sorter::AnySorter;
+use super::playable::Playable;
+use super::sort_item::{ItemKind, SortItem};
+use super::sorter::{generate_initial_data, SortStats, Sorter};
+
+pub const MIN_RACERS: usize = 2;
+pub const MAX_RACERS: usize = 6;
+
+#[derive(Clone, PartialEq)]
+pub struct Racer {
+    pub algorithm_name: String,
+    pub label: String,
+    pub sorter: AnySorter,
+    pub rank: Option<usize>,
+}
+
+impl Racer {
+    pub fn is_finished(&self) -> bool {
+        self.sorter.get_active_step() >= self.sorter.get_final_step()
+    }
+}
+
+/// Several sorters over the same input, advanced by one shared step counter
+#[derive(Clone, PartialEq)]
+pub struct Race {
+    pub racers: Vec<Racer>,
+    pub active_step: u32,
+    pub is_playing: bool,
+    steps_time: f32,
+}
+
+impl Race {
+    pub fn new(sort_config: &SortConfigValues, algorithms: &[String]) -> Race {
+        let racers = match sort_config.item_kind() {
+            ItemKind::Integers => racers(sort_config, algorithms, AnySorter::Integers),
+            ItemKind::Floats => racers(sort_config, algorithms, AnySorter::Floats),
+            ItemKind::Words => racers(sort_config, algorithms, AnySorter::Words),
+            ItemKind::Records => racers(sort_config, algorithms, AnySorter::Records),
+        };
+        Self {
+            racers,
+            active_step: 0,
+            is_playing: false,
+            steps_time: 0.,
+        }
+    }
+
+    pub fn max_steps(&self) -> u32 {
+        self.racers
+            .iter()
+            .map(|racer| racer.sorter.get_final_step())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn play(&mut self, sort_config: &SortConfigValues) {
+        self.is_playing = true;
+        if self.active_step >= self.max_steps() {
+            self.set_step(0);
+        }
+        // The slowest racer takes the whole configured time, the rest finish earlier
+        self.steps_time = sort_config.time_overall as f32 / self.max_steps().max(1) as f32 * MS_IN_SECS;
+    }
+
+    pub fn stop(&mut self) {
+        self.is_playing = false;
+    }
+
+    pub fn tick(&mut self) {
+        let max_steps = self.max_steps();
+        if self.active_step >= max_steps {
+            self.steps_time = 0.;
+            self.is_playing = false;
+            return;
+        }
+        let step_increment = (MAX_REFRESH_RATE / self.steps_time).ceil() as u32;
+        self.set_step((self.active_step + step_increment).min(max_steps));
+    }
+
+    pub fn tick_time(&self) -> u32 {
+        if self.steps_time == 0. || !self.is_playing {
+            return 0;
+        }
+        self.steps_time.max(MAX_REFRESH_RATE) as u32
+    }
+
+    pub fn set_step(&mut self, step: u32) {
+        for racer in self.racers.iter_mut() {
+            let racer_step = step.min(racer.sorter.get_final_step());
+            racer.sorter.set_step(racer_step);
+        }
+        self.active_step = step;
+        self.rank_finished();
+    }
+
+    // Fewer operations means a better place, racers with equal counts share one.
+    // Annotations and memory steps cost nothing, so they do not count against a racer.
+    fn rank_finished(&mut self) {
+        let counts: Vec<u32> = self.racers.iter().map(|racer| operations(&racer.sorter.stats())).collect();
+        for racer in self.racers.iter_mut() {
+            racer.rank = if racer.is_finished() {
+                let own = operations(&racer.sorter.stats());
+                Some(1 + counts.iter().filter(|&&other| other < own).count())
+            } else {
+                None
+            };
+        }
+    }
+}
+
+fn operations(stats: &SortStats) -> u32 {
+    stats.comparisons + stats.swaps + stats.writes
+}
+
+// The items are generated once, so every racer sorts the very same input
+fn racers<T: SortItem>(
+    sort_config: &SortConfigValues,
+    algorithms: &[String],
+    any_sorter: fn(Sorter<T>) -> AnySorter,
+) -> Vec<Racer> {
+    let data: Vec<T> = generate_initial_data(sort_config);
+    algorithms
+        .iter()
+        .take(MAX_RACERS)
+        .map(|algorithm_name| {
+            let mut racer_config = sort_config.clone();
+            racer_config.current_algorithm_name = algorithm_name.clone();
+            Racer {
+                algorithm_name: algorithm_name.clone(),
+                label: sort_config.algorithm_label(algorithm_name),
+                sorter: any_sorter(Sorter::from_data(data.clone(), &racer_config)),
+                rank: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_race_ranks_racers_as_they_finish() {
+        let mut config = SortConfigValues::new();
+        config.items_count = 50;
+        let algorithms = vec!["bubble_sort".to_string(), "merge_sort".to_string()];
+        let mut race = Race::new(&config, &algorithms);
+        assert_eq!(race.racers[0].sorter.magnitudes(), race.racers[1].sorter.magnitudes());
+
+        let merge_steps = race.racers[1].sorter.get_final_step();
+        assert!(merge_steps < race.max_steps());
+        race.set_step(merge_steps);
+        assert_eq!(race.racers[0].rank, None);
+        assert_eq!(race.racers[1].rank, Some(1));
+
+        race.set_step(race.max_steps());
+        assert_eq!(race.racers[0].rank, Some(2));
+        assert!(operations(&race.racers[1].sorter.stats()) < operations(&race.racers[0].sorter.stats()));
+        assert_eq!(race.racers[0].sorter.magnitudes(), race.racers[1].sorter.magnitudes());
+    }
+}
@@ -0,0 +1,56 @@
+use super::sorter::SortType;
+
+/// Pitch of the smallest value, the largest one sounds at `MAX_FREQUENCY`
+pub const MIN_FREQUENCY: f64 = 120.;
+pub const MAX_FREQUENCY: f64 = 1212.;
+// Fast playback would otherwise give clicks instead of tones, slow playback a drone
+const MIN_DURATION_MS: f64 = 30.;
+const MAX_DURATION_MS: f64 = 120.;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Tone {
+    pub frequency: f64,
+    pub duration_ms: f64,
+}
+
+/// Tone for a step that was just played, pitched by the value it moved
+///
+/// `data` is the array after the step, or after any later steps that move nothing,
+/// so a swap sounds the value now at its first index.
+/// Steps that move nothing are silent. Values map onto pitch rather than frequency,
+/// each doubling of the frequency covers the same share of `value_range`.
+pub fn tone_for_step(step: &SortType<f64>, data: &[f64], value_range: (f64, f64), tick_time_ms: u32) -> Option<Tone> {
+    let value = match *step {
+        SortType::Set(_, value) => value,
+        SortType::Swap(idx, _) => *data.get(idx)?,
+        _ => return None,
+    };
+    let (min, max) = value_range;
+    let position = if max > min { ((value - min) / (max - min)).clamp(0., 1.) } else { 0.5 };
+    Some(Tone {
+        frequency: MIN_FREQUENCY * (MAX_FREQUENCY / MIN_FREQUENCY).powf(position),
+        duration_ms: (tick_time_ms as f64).clamp(MIN_DURATION_MS, MAX_DURATION_MS),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tones_follow_the_moved_value() {
+        let data = [1., 5., 3.];
+        let range = (1., 5.);
+        let tone = |step: SortType<f64>| tone_for_step(&step, &data, range, 50);
+
+        assert_eq!(tone(SortType::Set(2, 1.)).unwrap().frequency, MIN_FREQUENCY);
+        assert_eq!(tone(SortType::Swap(1, 0)).unwrap().frequency, MAX_FREQUENCY);
+        let middle = tone(SortType::Swap(2, 0)).unwrap().frequency;
+        assert!((middle - (MIN_FREQUENCY * MAX_FREQUENCY).sqrt()).abs() < 1e-9);
+        assert_eq!(tone(SortType::Compare(0, 1)), None);
+        assert_eq!(tone(SortType::Swap(3, 0)), None);
+
+        assert_eq!(tone_for_step(&SortType::Set(0, 7.), &data, (7., 7.), 0).unwrap().duration_ms, MIN_DURATION_MS);
+        assert_eq!(tone_for_step(&SortType::Set(0, 9.), &data, range, 1000).unwrap(), Tone { frequency: MAX_FREQUENCY, duration_ms: MAX_DURATION_MS });
+    }
+}
@@ -0,0 +1,304 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::parse_custom_input;
+
+/// What the sorter needs from the values it sorts besides their order
+pub trait SortItem: Copy + Ord + fmt::Debug + fmt::Display + Serialize + DeserializeOwned + 'static {
+    /// Whether `int_key` follows the order of the values, the counting and radix sorts need that
+    const HAS_INT_KEY: bool;
+
+    /// Integer in the same order as the values, only meaningful with `HAS_INT_KEY`
+    fn int_key(&self) -> i64;
+    /// Height of the value's bar, never smaller for a larger value
+    fn magnitude(&self) -> f64;
+    /// Reads one value typed into the custom input
+    fn parse(input: &str) -> Result<Self, String>;
+    /// Value standing for the generated integer `value` out of `1..=items_count`,
+    /// in the same order as the integers so every distribution keeps its shape
+    fn from_generated(value: i32, idx: usize, items_count: usize) -> Self;
+}
+
+impl SortItem for i32 {
+    const HAS_INT_KEY: bool = true;
+
+    fn int_key(&self) -> i64 {
+        *self as i64
+    }
+
+    fn magnitude(&self) -> f64 {
+        *self as f64
+    }
+
+    fn parse(input: &str) -> Result<i32, String> {
+        input.parse::<i32>().map_err(|_| format!("\"{}\" is not a whole number", input))
+    }
+
+    fn from_generated(value: i32, _idx: usize, _items_count: usize) -> i32 {
+        value
+    }
+}
+
+/// A float ordered by `f64::total_cmp`, so every value including NaN has a place
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TotalFloat(pub f64);
+
+impl PartialEq for TotalFloat {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TotalFloat {}
+
+impl PartialOrd for TotalFloat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalFloat {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl fmt::Display for TotalFloat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl SortItem for TotalFloat {
+    const HAS_INT_KEY: bool = false;
+
+    fn int_key(&self) -> i64 {
+        0
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.0
+    }
+
+    fn parse(input: &str) -> Result<TotalFloat, String> {
+        input.parse::<f64>().map(TotalFloat).map_err(|_| format!("\"{}\" is not a number", input))
+    }
+
+    fn from_generated(value: i32, _idx: usize, _items_count: usize) -> TotalFloat {
+        TotalFloat(value as f64 / 10.)
+    }
+}
+
+pub const WORD_LEN: usize = 12;
+
+// Sorted, so generated integers map onto words in the same order
+const WORDS: [&str; 40] = [
+    "ant", "bat", "bear", "bee", "cat", "cow", "crab", "crow", "deer", "dog",
+    "dove", "duck", "eel", "elk", "emu", "fox", "frog", "goat", "gull", "hare",
+    "hawk", "ibis", "jay", "kiwi", "lark", "lion", "lynx", "mole", "moth", "mule",
+    "newt", "owl", "puma", "rat", "seal", "swan", "toad", "wasp", "wolf", "yak",
+];
+
+/// A short string kept inline so it can be copied like a number, ordered by its bytes
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Word {
+    // Padded with zeros, which sort before any byte, so shorter prefixes come first
+    bytes: [u8; WORD_LEN],
+    len: u8,
+}
+
+impl Word {
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.bytes[..self.len as usize]).unwrap()
+    }
+}
+
+impl TryFrom<String> for Word {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Word, String> {
+        Word::parse(&value)
+    }
+}
+
+impl From<Word> for String {
+    fn from(word: Word) -> String {
+        word.as_str().to_string()
+    }
+}
+
+impl fmt::Debug for Word {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl SortItem for Word {
+    const HAS_INT_KEY: bool = false;
+
+    fn int_key(&self) -> i64 {
+        0
+    }
+
+    // The first three bytes as one number, enough to tell the bars apart
+    fn magnitude(&self) -> f64 {
+        self.bytes[..3].iter().fold(0., |total, &byte| total * 256. + byte as f64)
+    }
+
+    fn parse(input: &str) -> Result<Word, String> {
+        if input.is_empty() || input.len() > WORD_LEN {
+            return Err(format!("\"{}\" should have 1 to {} letters", input, WORD_LEN));
+        }
+        let mut bytes = [0; WORD_LEN];
+        bytes[..input.len()].copy_from_slice(input.as_bytes());
+        Ok(Word { bytes, len: input.len() as u8 })
+    }
+
+    fn from_generated(value: i32, _idx: usize, items_count: usize) -> Word {
+        let position = (value.max(1) as usize - 1) * WORDS.len() / items_count.max(1);
+        Word::parse(WORDS[position.min(WORDS.len() - 1)]).unwrap()
+    }
+}
+
+/// A small struct sorted by its key alone, the name travels along with it
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Record {
+    pub key: i32,
+    pub name: Word,
+}
+
+impl PartialEq for Record {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Record {}
+
+impl PartialOrd for Record {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Record {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.key, self.name)
+    }
+}
+
+impl SortItem for Record {
+    const HAS_INT_KEY: bool = true;
+
+    fn int_key(&self) -> i64 {
+        self.key as i64
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.key as f64
+    }
+
+    /// Written as `key:name`
+    fn parse(input: &str) -> Result<Record, String> {
+        let (key, name) = input
+            .split_once(':')
+            .ok_or_else(|| format!("\"{}\" should look like key:name", input))?;
+        Ok(Record { key: i32::parse(key)?, name: Word::parse(name)? })
+    }
+
+    // Names follow the starting positions, so they show where each record came from
+    fn from_generated(value: i32, idx: usize, _items_count: usize) -> Record {
+        Record { key: value, name: Word::parse(WORDS[idx % WORDS.len()]).unwrap() }
+    }
+}
+
+/// Which of the item types above a sort runs on
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ItemKind {
+    Integers,
+    Floats,
+    Words,
+    Records,
+}
+
+impl ItemKind {
+    pub fn from_string(s: &str) -> Result<ItemKind, &'static str> {
+        match s {
+            "integers" => Ok(ItemKind::Integers),
+            "floats" => Ok(ItemKind::Floats),
+            "words" => Ok(ItemKind::Words),
+            "records" => Ok(ItemKind::Records),
+            _ => Err("Invalid variant"),
+        }
+    }
+
+    pub fn as_name(&self) -> &'static str {
+        match self {
+            ItemKind::Integers => "integers",
+            ItemKind::Floats => "floats",
+            ItemKind::Words => "words",
+            ItemKind::Records => "records",
+        }
+    }
+
+    pub fn has_int_key(&self) -> bool {
+        match self {
+            ItemKind::Integers => i32::HAS_INT_KEY,
+            ItemKind::Floats => TotalFloat::HAS_INT_KEY,
+            ItemKind::Words => Word::HAS_INT_KEY,
+            ItemKind::Records => Record::HAS_INT_KEY,
+        }
+    }
+
+    /// Number of values in a custom input, or why it cannot be read
+    pub fn check_custom_input(&self, input: &str) -> Result<usize, String> {
+        match self {
+            ItemKind::Integers => parse_custom_input::<i32>(input).map(|items| items.len()),
+            ItemKind::Floats => parse_custom_input::<TotalFloat>(input).map(|items| items.len()),
+            ItemKind::Words => parse_custom_input::<Word>(input).map(|items| items.len()),
+            ItemKind::Records => parse_custom_input::<Record>(input).map(|items| items.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_items_keep_the_order_of_what_they_are_made_from() {
+        let words: Vec<Word> = (1..=80).map(|value| Word::from_generated(value, 0, 80)).collect();
+        assert!(words.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(words.windows(2).all(|pair| pair[0].magnitude() <= pair[1].magnitude()));
+        assert!(Word::parse("ant").unwrap() < Word::parse("anteater").unwrap());
+
+        let floats = parse_custom_input::<TotalFloat>("2.5, -0, 0, NaN, -1e3").unwrap();
+        let mut sorted = floats.clone();
+        sorted.sort();
+        assert_eq!(sorted.iter().map(|value| value.to_string()).collect::<Vec<_>>(), ["-1000", "-0", "0", "2.5", "NaN"]);
+
+        let records = parse_custom_input::<Record>("3:ann 1:bob 3:cy").unwrap();
+        assert_eq!(records[0], records[2]);
+        assert!(records[1] < records[0]);
+        assert!(parse_custom_input::<Record>("3ann").is_err());
+        assert!(ItemKind::Words.check_custom_input("averyverylongword").is_err());
+        assert_eq!(ItemKind::Words.check_custom_input("b, a c"), Ok(3));
+    }
+}
@@ -0,0 +1,132 @@
+use std::cmp::Ordering;
+
+use crate::config::sort_config::SortConfigValues;
+use crate::sorting_algorithms::key_span;
+
+use super::sort_item::SortItem;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    pub fn from_string(s: &str) -> Result<SortOrder, &'static str> {
+        match s {
+            "ascending" => Ok(SortOrder::Ascending),
+            "descending" => Ok(SortOrder::Descending),
+            _ => Err("Invalid variant"),
+        }
+    }
+}
+
+/// What items are compared by, every mode but `Value` needs an integer key
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum KeyMode {
+    Value,
+    LastDigit,
+    /// Even values first, then odd ones, each group by value
+    ParityThenValue,
+}
+
+impl KeyMode {
+    pub fn from_string(s: &str) -> Result<KeyMode, &'static str> {
+        match s {
+            "value" => Ok(KeyMode::Value),
+            "last_digit" => Ok(KeyMode::LastDigit),
+            "parity_then_value" => Ok(KeyMode::ParityThenValue),
+            _ => Err("Invalid variant"),
+        }
+    }
+}
+
+/// The order a sort puts its items in, as a comparator and as an integer key
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SortKey {
+    pub order: SortOrder,
+    pub mode: KeyMode,
+    // Smallest integer key and how many keys fit between it and the largest,
+    // parity then value puts all odd keys past every even one with them
+    min: i64,
+    span: i64,
+}
+
+impl SortKey {
+    /// Key for sorting `items`, items without an integer key are always compared by value
+    pub fn for_items<T: SortItem>(sort_config: &SortConfigValues, items: &[T]) -> SortKey {
+        let order = SortOrder::from_string(&sort_config.sort_order).unwrap_or(SortOrder::Ascending);
+        let mode = if T::HAS_INT_KEY {
+            KeyMode::from_string(&sort_config.key_mode).unwrap_or(KeyMode::Value)
+        } else {
+            KeyMode::Value
+        };
+        let min = items.iter().map(SortItem::int_key).min().unwrap_or(0);
+        let max = items.iter().map(SortItem::int_key).max().unwrap_or(0);
+        SortKey { order, mode, min, span: max - min + 1 }
+    }
+
+    /// Integer in the order the items are sorted in, for the sorts that count or split keys
+    pub fn key(&self, value: i64) -> i64 {
+        let key = match self.mode {
+            KeyMode::Value => value,
+            KeyMode::LastDigit => (value % 10).abs(),
+            KeyMode::ParityThenValue => value.rem_euclid(2) * self.span + value - self.min,
+        };
+        match self.order {
+            SortOrder::Ascending => key,
+            SortOrder::Descending => -key,
+        }
+    }
+
+    /// Number of keys from the smallest to the largest one of `items`
+    pub fn key_span<T: SortItem>(&self, items: &[T]) -> u64 {
+        key_span(items, &|item: &T| self.key(item.int_key()))
+    }
+
+    /// Compares two items, `int_key` is only used by the modes that need it
+    pub fn compare<T: Ord>(&self, a: &T, b: &T, int_key: impl Fn(&T) -> i64) -> Ordering {
+        let ordering = match self.mode {
+            KeyMode::Value => a.cmp(b),
+            KeyMode::LastDigit => (int_key(a) % 10).abs().cmp(&(int_key(b) % 10).abs()),
+            KeyMode::ParityThenValue => {
+                let (a, b) = (int_key(a), int_key(b));
+                (a.rem_euclid(2), a).cmp(&(b.rem_euclid(2), b))
+            }
+        };
+        match self.order {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        }
+    }
+}
+
+impl Default for SortKey {
+    fn default() -> Self {
+        SortKey { order: SortOrder::Ascending, mode: KeyMode::Value, min: 0, span: 1 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_follows_the_comparator() {
+        let items = [-7, -2, 0, 3, 8, 13, 21, 40];
+        let mut config = SortConfigValues::new();
+        for order in ["ascending", "descending"] {
+            for mode in ["value", "last_digit", "parity_then_value"] {
+                config.sort_order = order.to_string();
+                config.key_mode = mode.to_string();
+                let sort_key = SortKey::for_items(&config, &items);
+                for a in items {
+                    for b in items {
+                        let by_key = sort_key.key(a as i64).cmp(&sort_key.key(b as i64));
+                        assert_eq!(by_key, sort_key.compare(&a, &b, |&item| item as i64), "{} {} {} {}", order, mode, a, b);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,38 +1,250 @@
 use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
 
-use crate::components::sorting_page::sorting_config::SortConfigValues;
-use crate::helpers::{MAX_REFRESH_RATE, get_new_generation, MS_IN_SECS};
+use serde::{Deserialize, Serialize};
+
+use crate::config::sort_config::SortConfigValues;
+use crate::helpers::{MAX_REFRESH_RATE, get_new_generation, parse_custom_input, Distribution, MS_IN_SECS};
+use crate::sorting_algorithms as algorithms;
 use crate::sorting_algorithms::bubble_sort::bubble_sort;
+use crate::sorting_algorithms::bucket_sort::bucket_sort;
+use crate::sorting_algorithms::counting_sort::counting_sort;
 use crate::sorting_algorithms::heap_sort::heap_sort;
 use crate::sorting_algorithms::insertion_sort::insertion_sort;
+use crate::sorting_algorithms::intro_sort::intro_sort;
 use crate::sorting_algorithms::merge_sort::merge_sort;
-use crate::sorting_algorithms::quick_sort::quick_sort;
+use crate::sorting_algorithms::pdq_sort::pdq_sort;
+use crate::sorting_algorithms::pigeonhole_sort::pigeonhole_sort;
+use crate::sorting_algorithms::quick_sort::{quick_sort, PartitionScheme, PivotChoice};
+use crate::sorting_algorithms::radix_sort::{radix_sort_lsd, radix_sort_msd};
 use crate::sorting_algorithms::shell_sort::shell_sort;
+use crate::sorting_algorithms::tim_sort::tim_sort;
+use crate::sorting_algorithms::{Comparator, KeyFn};
 
 use super::playable::Playable;
+use super::sort_item::SortItem;
+use super::sort_key::SortKey;
+use super::stability::{unstable_pairs, Tagged};
+use super::timeline::{Replay, Timeline};
+use super::trace::{SortTrace, TRACE_VERSION};
 
-#[derive(Clone, PartialEq, Debug, Copy)]
+#[derive(Clone, PartialEq, Debug, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SortType<T> {
     Set(usize, T),
     Swap(usize, usize),
+    Compare(usize, usize),
+    Alloc(usize),
+    Free(usize),
+    Bucket(usize, usize),
+    Digit(u32),
+    AuxSet(usize, usize),
+    Run(usize, usize),
+    // Annotations only describe what the algorithm is doing and leave the items alone
+    MarkPivot(usize),
+    /// The items from the first index up to the second are in their final place
+    MarkSorted(usize, usize),
+    /// The algorithm works on the items from the first index up to the second
+    FocusRange(usize, usize),
+    /// The heap ends before this index
+    HeapBoundary(usize),
+    /// Pseudocode line the following steps come from, only lives while a trace is recorded
+    Line(usize),
+}
+
+impl<T> SortType<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> SortType<U> {
+        match self {
+            SortType::Set(idx, value) => SortType::Set(idx, f(value)),
+            SortType::Swap(idx1, idx2) => SortType::Swap(idx1, idx2),
+            SortType::Compare(idx1, idx2) => SortType::Compare(idx1, idx2),
+            SortType::Alloc(size) => SortType::Alloc(size),
+            SortType::Free(size) => SortType::Free(size),
+            SortType::Bucket(idx, bucket) => SortType::Bucket(idx, bucket),
+            SortType::Digit(position) => SortType::Digit(position),
+            SortType::AuxSet(idx, value) => SortType::AuxSet(idx, value),
+            SortType::Run(lo, hi) => SortType::Run(lo, hi),
+            SortType::MarkPivot(idx) => SortType::MarkPivot(idx),
+            SortType::MarkSorted(lo, hi) => SortType::MarkSorted(lo, hi),
+            SortType::FocusRange(lo, hi) => SortType::FocusRange(lo, hi),
+            SortType::HeapBoundary(end) => SortType::HeapBoundary(end),
+            SortType::Line(line) => SortType::Line(line),
+        }
+    }
+
+    /// Steps that only mark up the array or book memory. Playback applies them together
+    /// with the next step that moves or compares items, and step counts leave them out.
+    pub fn is_silent(&self) -> bool {
+        matches!(
+            self,
+            SortType::Alloc(_)
+                | SortType::Free(_)
+                | SortType::MarkPivot(_)
+                | SortType::MarkSorted(..)
+                | SortType::FocusRange(..)
+                | SortType::HeapBoundary(_)
+        )
+    }
+
+    /// Moves the indexes of a step emitted on a subslice into the coordinates of the whole array.
+    pub fn shifted(self, offset: usize) -> Self {
+        match self {
+            SortType::Set(idx, value) => SortType::Set(idx + offset, value),
+            SortType::Swap(idx1, idx2) => SortType::Swap(idx1 + offset, idx2 + offset),
+            SortType::Compare(idx1, idx2) => SortType::Compare(idx1 + offset, idx2 + offset),
+            SortType::Bucket(idx, bucket) => SortType::Bucket(idx + offset, bucket),
+            SortType::Run(lo, hi) => SortType::Run(lo + offset, hi + offset),
+            SortType::MarkPivot(idx) => SortType::MarkPivot(idx + offset),
+            SortType::MarkSorted(lo, hi) => SortType::MarkSorted(lo + offset, hi + offset),
+            SortType::FocusRange(lo, hi) => SortType::FocusRange(lo + offset, hi + offset),
+            SortType::HeapBoundary(end) => SortType::HeapBoundary(end + offset),
+            SortType::Alloc(_)
+            | SortType::Free(_)
+            | SortType::Digit(_)
+            | SortType::AuxSet(..)
+            | SortType::Line(_) => self,
+        }
+    }
 }
 
-impl ToString for SortType<i32> {
-    fn to_string(&self) -> String {
+impl<T: fmt::Display> fmt::Display for SortType<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self {
             SortType::Swap(idx1, idx2) => {
                 if idx1 == idx2 && idx1 == &0 {
-                    String::new()
+                    Ok(())
                 } else {
-                    format!("Swap indexes {} and {}", idx1, idx2)
+                    write!(f, "Swap indexes {} and {}", idx1, idx2)
                 }
             },
-            SortType::Set(idx, value) => format!("Set index {} to {}", idx, value),
+            SortType::Set(idx, value) => write!(f, "Set index {} to {}", idx, value),
+            SortType::Compare(idx1, idx2) => write!(f, "Compare indexes {} and {}", idx1, idx2),
+            SortType::Alloc(size) => write!(f, "Allocate {} auxiliary items", size),
+            SortType::Free(size) => write!(f, "Free {} auxiliary items", size),
+            SortType::Bucket(idx, bucket) => write!(f, "Move index {} into bucket {}", idx, bucket),
+            SortType::Digit(position) => write!(f, "Sort by digit {}", position),
+            SortType::AuxSet(idx, value) => write!(f, "Set auxiliary index {} to {}", idx, value),
+            SortType::Run(lo, hi) => write!(f, "Run over indexes {}..{}", lo, hi),
+            SortType::MarkPivot(idx) => write!(f, "Pivot at index {}", idx),
+            SortType::MarkSorted(lo, hi) => write!(f, "Indexes {}..{} are in place", lo, hi),
+            SortType::FocusRange(lo, hi) => write!(f, "Work on indexes {}..{}", lo, hi),
+            SortType::HeapBoundary(end) => write!(f, "Heap ends before index {}", end),
+            SortType::Line(line) => write!(f, "Line {}", line + 1),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct SortStats {
+    pub comparisons: u32,
+    pub swaps: u32,
+    pub writes: u32,
+    pub aux_memory: usize,
+    pub peak_aux_memory: usize,
+}
+
+impl SortStats {
+    pub fn from_steps<'a, T: 'a>(steps: impl Iterator<Item = &'a SortType<T>>) -> Self {
+        let mut stats = Self::default();
+        for step in steps {
+            stats.record(step);
+        }
+        stats
+    }
+
+    pub fn record<T>(&mut self, step: &SortType<T>) {
+        match step {
+            SortType::Set(..) => self.writes += 1,
+            SortType::Swap(..) => self.swaps += 1,
+            SortType::Compare(..) => self.comparisons += 1,
+            SortType::Alloc(size) => {
+                self.aux_memory += size;
+                self.peak_aux_memory = self.peak_aux_memory.max(self.aux_memory);
+            }
+            SortType::Free(size) => self.aux_memory = self.aux_memory.saturating_sub(*size),
+            SortType::Bucket(..)
+            | SortType::Digit(..)
+            | SortType::AuxSet(..)
+            | SortType::Run(..)
+            | SortType::MarkPivot(_)
+            | SortType::MarkSorted(..)
+            | SortType::FocusRange(..)
+            | SortType::HeapBoundary(_)
+            | SortType::Line(_) => (),
+        }
+    }
+}
+
+/// What the annotation steps have marked so far
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Annotations {
+    /// Follows the pivot item through swaps
+    pub pivot: Option<usize>,
+    pub focus: Option<(usize, usize)>,
+    pub heap_end: Option<usize>,
+    /// Ranges of items in their final place, in order and never touching each other
+    pub sorted: Vec<(usize, usize)>,
+}
+
+/// What an annotation step overwrote
+#[derive(Clone, PartialEq, Debug)]
+enum AnnotationUndo {
+    Nothing,
+    Pivot(Option<usize>),
+    Focus(Option<(usize, usize)>, Option<usize>),
+    HeapEnd(Option<usize>),
+    /// Where the merged sorted range went and the ranges it took the place of
+    Sorted(usize, Vec<(usize, usize)>),
+}
+
+impl Annotations {
+    fn apply<T>(&mut self, step: &SortType<T>) -> AnnotationUndo {
+        match *step {
+            SortType::MarkPivot(idx) => AnnotationUndo::Pivot(self.pivot.replace(idx)),
+            // The pivot of the previous range means nothing in the next one
+            SortType::FocusRange(lo, hi) => AnnotationUndo::Focus(self.focus.replace((lo, hi)), self.pivot.take()),
+            SortType::HeapBoundary(end) => AnnotationUndo::HeapEnd(self.heap_end.replace(end)),
+            SortType::MarkSorted(mut lo, mut hi) if lo < hi => {
+                let first = self.sorted.partition_point(|&(_, range_hi)| range_hi < lo);
+                let last = self.sorted.partition_point(|&(range_lo, _)| range_lo <= hi);
+                if first < last {
+                    lo = lo.min(self.sorted[first].0);
+                    hi = hi.max(self.sorted[last - 1].1);
+                }
+                AnnotationUndo::Sorted(first, self.sorted.splice(first..last, [(lo, hi)]).collect())
+            }
+            SortType::Swap(idx1, idx2) => {
+                let pivot = self.pivot;
+                if pivot == Some(idx1) {
+                    self.pivot = Some(idx2);
+                } else if pivot == Some(idx2) {
+                    self.pivot = Some(idx1);
+                }
+                AnnotationUndo::Pivot(pivot)
+            }
+            _ => AnnotationUndo::Nothing,
+        }
+    }
+
+    fn revert(&mut self, undo: &AnnotationUndo) {
+        match undo {
+            AnnotationUndo::Nothing => (),
+            AnnotationUndo::Pivot(pivot) => self.pivot = *pivot,
+            AnnotationUndo::Focus(focus, pivot) => {
+                self.focus = *focus;
+                self.pivot = *pivot;
+            }
+            AnnotationUndo::HeapEnd(heap_end) => self.heap_end = *heap_end,
+            AnnotationUndo::Sorted(at, ranges) => {
+                self.sorted.splice(*at..at + 1, ranges.iter().copied());
+            }
         }
     }
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[allow(clippy::enum_variant_names)]
 enum SortingAlgorithmEnum {
     MergeSort,
     BubbleSort,
@@ -41,6 +253,13 @@ enum SortingAlgorithmEnum {
     InsertionSort,
     ShellSort,
     BucketSort,
+    RadixSortLsd,
+    RadixSortMsd,
+    CountingSort,
+    PigeonholeSort,
+    IntroSort,
+    PdqSort,
+    TimSort,
 }
 
 impl SortingAlgorithmEnum {
@@ -53,11 +272,51 @@ impl SortingAlgorithmEnum {
             "insertion_sort" => Ok(SortingAlgorithmEnum::InsertionSort),
             "shell_sort" => Ok(SortingAlgorithmEnum::ShellSort),
             "bucket_sort" => Ok(SortingAlgorithmEnum::BucketSort),
+            "radix_sort_lsd" => Ok(SortingAlgorithmEnum::RadixSortLsd),
+            "radix_sort_msd" => Ok(SortingAlgorithmEnum::RadixSortMsd),
+            "counting_sort" => Ok(SortingAlgorithmEnum::CountingSort),
+            "pigeonhole_sort" => Ok(SortingAlgorithmEnum::PigeonholeSort),
+            "intro_sort" => Ok(SortingAlgorithmEnum::IntroSort),
+            "pdq_sort" => Ok(SortingAlgorithmEnum::PdqSort),
+            "tim_sort" => Ok(SortingAlgorithmEnum::TimSort),
             _ => Err("Invalid variant"),
         }
     }
+
+    /// Sorts that count or split integer keys, they cannot run on items without one
+    fn needs_int_key(&self) -> bool {
+        matches!(
+            self,
+            SortingAlgorithmEnum::BucketSort
+                | SortingAlgorithmEnum::RadixSortLsd
+                | SortingAlgorithmEnum::RadixSortMsd
+                | SortingAlgorithmEnum::CountingSort
+                | SortingAlgorithmEnum::PigeonholeSort
+        )
+    }
+
+    fn pseudocode(&self, partition_scheme: PartitionScheme) -> Vec<&'static str> {
+        match self {
+            SortingAlgorithmEnum::QuickSort => algorithms::quick_sort::pseudocode(partition_scheme),
+            SortingAlgorithmEnum::MergeSort => algorithms::merge_sort::PSEUDOCODE.to_vec(),
+            SortingAlgorithmEnum::BubbleSort => algorithms::bubble_sort::PSEUDOCODE.to_vec(),
+            SortingAlgorithmEnum::HeapSort => algorithms::heap_sort::PSEUDOCODE.to_vec(),
+            SortingAlgorithmEnum::InsertionSort => algorithms::insertion_sort::PSEUDOCODE.to_vec(),
+            SortingAlgorithmEnum::ShellSort => algorithms::shell_sort::PSEUDOCODE.to_vec(),
+            SortingAlgorithmEnum::BucketSort => algorithms::bucket_sort::PSEUDOCODE.to_vec(),
+            SortingAlgorithmEnum::RadixSortLsd => algorithms::radix_sort::LSD_PSEUDOCODE.to_vec(),
+            SortingAlgorithmEnum::RadixSortMsd => algorithms::radix_sort::MSD_PSEUDOCODE.to_vec(),
+            SortingAlgorithmEnum::CountingSort => algorithms::counting_sort::PSEUDOCODE.to_vec(),
+            SortingAlgorithmEnum::PigeonholeSort => algorithms::pigeonhole_sort::PSEUDOCODE.to_vec(),
+            SortingAlgorithmEnum::IntroSort => algorithms::intro_sort::PSEUDOCODE.to_vec(),
+            SortingAlgorithmEnum::PdqSort => algorithms::pdq_sort::PSEUDOCODE.to_vec(),
+            SortingAlgorithmEnum::TimSort => algorithms::tim_sort::PSEUDOCODE.to_vec(),
+        }
+    }
 }
 
+type SortFn<T> = Box<dyn Fn(&mut [T], &mut VecDeque<SortType<T>>)>;
+
 struct SortAlgorithm;
 
 impl SortAlgorithm {
@@ -65,71 +324,443 @@ impl SortAlgorithm {
         Self
     }
 
-    pub fn do_sort(&self, sort_config: &SortConfigValues, items: &mut Vec<i32>, steps: &mut VecDeque<SortType<i32>>) {
-        (SortAlgorithm::from(sort_config))(items, steps)
+    pub fn do_sort<T: Copy + Ord + Into<i64> + 'static>(
+        &self,
+        sort_config: &SortConfigValues,
+        sort_key: SortKey,
+        items: &mut [T],
+        steps: &mut VecDeque<SortType<T>>,
+    ) {
+        (SortAlgorithm::from(sort_config, sort_key))(items, steps)
     }
 
-    pub fn from(sort_config: &SortConfigValues) -> fn(&mut Vec<i32>, &mut VecDeque<SortType<i32>>) {
+    pub fn from<T: Copy + Ord + Into<i64> + 'static>(sort_config: &SortConfigValues, sort_key: SortKey) -> SortFn<T> {
         let result = SortingAlgorithmEnum::from_string(sort_config.current_algorithm_name.clone());
         match result {
-            Ok(v) => SortAlgorithm::from_enum(v),
-            Err(_) => SortAlgorithm::from_enum(SortingAlgorithmEnum::MergeSort)
+            Ok(v) => SortAlgorithm::from_enum(v, sort_config, sort_key),
+            Err(_) => SortAlgorithm::from_enum(SortingAlgorithmEnum::MergeSort, sort_config, sort_key)
         }
     }
 
-    fn from_enum(enum_value: SortingAlgorithmEnum) -> fn(&mut Vec<i32>, &mut VecDeque<SortType<i32>>) {
+    fn from_enum<T: Copy + Ord + Into<i64> + 'static>(
+        enum_value: SortingAlgorithmEnum,
+        sort_config: &SortConfigValues,
+        sort_key: SortKey,
+    ) -> SortFn<T> {
+        let radix_base = sort_config.radix_base;
+        let seed = sort_config.seed;
+        let partition_scheme = PartitionScheme::from_string(&sort_config.partition_scheme)
+            .unwrap_or(PartitionScheme::Lomuto);
+        let pivot_choice = PivotChoice::from_string(&sort_config.pivot_choice)
+            .unwrap_or(PivotChoice::Middle);
+        let cmp = move |a: &T, b: &T| sort_key.compare(a, b, |&item| item.into());
+        let key = move |item: &T| sort_key.key((*item).into());
+        let with_cmp = move |sort: fn(&mut [T], &mut VecDeque<SortType<T>>, &Comparator<T>)| -> SortFn<T> {
+            Box::new(move |items, steps| sort(items, steps, &cmp))
+        };
+        let with_key = move |sort: fn(&mut [T], &mut VecDeque<SortType<T>>, &KeyFn<T>)| -> SortFn<T> {
+            Box::new(move |items, steps| sort(items, steps, &key))
+        };
         match enum_value {
-            SortingAlgorithmEnum::MergeSort => merge_sort::<i32>,
-            SortingAlgorithmEnum::BubbleSort => bubble_sort::<i32>,
-            SortingAlgorithmEnum::HeapSort => heap_sort::<i32>,
-            SortingAlgorithmEnum::QuickSort => quick_sort::<i32>,
-            SortingAlgorithmEnum::InsertionSort => insertion_sort::<i32>,
-            SortingAlgorithmEnum::ShellSort => shell_sort::<i32>,
-            SortingAlgorithmEnum::BucketSort => todo!(),
+            SortingAlgorithmEnum::MergeSort => with_cmp(merge_sort::<T>),
+            SortingAlgorithmEnum::BubbleSort => with_cmp(bubble_sort::<T>),
+            SortingAlgorithmEnum::HeapSort => with_cmp(heap_sort::<T>),
+            SortingAlgorithmEnum::QuickSort => {
+                Box::new(move |items, steps| quick_sort(items, steps, &cmp, partition_scheme, pivot_choice, seed))
+            }
+            SortingAlgorithmEnum::InsertionSort => with_cmp(insertion_sort::<T>),
+            SortingAlgorithmEnum::ShellSort => with_cmp(shell_sort::<T>),
+            SortingAlgorithmEnum::BucketSort => with_key(bucket_sort::<T>),
+            SortingAlgorithmEnum::RadixSortLsd => {
+                Box::new(move |items, steps| radix_sort_lsd(items, steps, &key, radix_base))
+            }
+            SortingAlgorithmEnum::RadixSortMsd => {
+                Box::new(move |items, steps| radix_sort_msd(items, steps, &key, radix_base))
+            }
+            SortingAlgorithmEnum::CountingSort => with_key(counting_sort::<T>),
+            SortingAlgorithmEnum::PigeonholeSort => with_key(pigeonhole_sort::<T>),
+            SortingAlgorithmEnum::IntroSort => with_cmp(intro_sort::<T>),
+            SortingAlgorithmEnum::PdqSort => with_cmp(pdq_sort::<T>),
+            SortingAlgorithmEnum::TimSort => with_cmp(tim_sort::<T>),
+        }
+    }
+}
+
+pub fn needs_int_key(algorithm_name: &str) -> bool {
+    SortingAlgorithmEnum::from_string(algorithm_name.to_string()).is_ok_and(|algorithm| algorithm.needs_int_key())
+}
+
+/// Whether the algorithm keeps a slot for every key between the smallest and the largest
+pub fn allocates_per_key(algorithm_name: &str) -> bool {
+    matches!(
+        SortingAlgorithmEnum::from_string(algorithm_name.to_string()),
+        Ok(SortingAlgorithmEnum::CountingSort | SortingAlgorithmEnum::PigeonholeSort)
+    )
+}
+
+/// Everything a sort trace changes while it is replayed
+#[derive(Clone, PartialEq, Debug)]
+struct SortState<T: SortItem> {
+    items: Vec<Tagged<T>>,
+    aux: Vec<usize>,
+    stats: SortStats,
+    digit: Option<u32>,
+    runs: Vec<(usize, usize)>,
+    annotations: Annotations,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum SortUndo<T: SortItem> {
+    Nothing,
+    Item(Tagged<T>),
+    Aux(usize),
+    Memory(usize, usize),
+    Digit(Option<u32>),
+    /// Where the reported run went and the runs it took the place of
+    Runs(usize, Vec<(usize, usize)>),
+    Annotations(AnnotationUndo),
+}
+
+impl<T: SortItem> SortState<T> {
+    fn new(initial_data: &[T], steps: &VecDeque<SortType<Tagged<T>>>) -> Self {
+        // Auxiliary structures start zeroed and are sized by the largest index the run touches
+        let aux_len = steps.iter().filter_map(|step| match step {
+            SortType::AuxSet(idx, _) => Some(idx + 1),
+            _ => None,
+        }).max().unwrap_or(0);
+        Self {
+            items: Tagged::tag_all(initial_data),
+            aux: vec![0; aux_len],
+            stats: SortStats::default(),
+            digit: None,
+            runs: Vec::new(),
+            annotations: Annotations::default(),
+        }
+    }
+}
+
+impl<T: SortItem> Replay<SortType<Tagged<T>>> for SortState<T> {
+    type Undo = SortUndo<T>;
+
+    fn apply(&mut self, step: &SortType<Tagged<T>>) -> SortUndo<T> {
+        let memory = SortUndo::Memory(self.stats.aux_memory, self.stats.peak_aux_memory);
+        self.stats.record(step);
+        match *step {
+            SortType::Set(idx, item) => SortUndo::Item(std::mem::replace(&mut self.items[idx], item)),
+            SortType::Swap(idx1, idx2) => {
+                self.items.swap(idx1, idx2);
+                self.annotations.apply(step);
+                SortUndo::Nothing
+            }
+            SortType::AuxSet(idx, value) => SortUndo::Aux(std::mem::replace(&mut self.aux[idx], value)),
+            SortType::Digit(position) => SortUndo::Digit(self.digit.replace(position)),
+            // A newly reported run replaces every run it overlaps, which is how merges show up
+            SortType::Run(lo, hi) => {
+                let first = self.runs.partition_point(|&(_, run_hi)| run_hi <= lo);
+                let last = self.runs.partition_point(|&(run_lo, _)| run_lo < hi);
+                SortUndo::Runs(first, self.runs.splice(first..last, [(lo, hi)]).collect())
+            }
+            SortType::Alloc(_) | SortType::Free(_) => memory,
+            SortType::MarkPivot(_) | SortType::MarkSorted(..) | SortType::FocusRange(..) | SortType::HeapBoundary(_) => {
+                SortUndo::Annotations(self.annotations.apply(step))
+            }
+            SortType::Compare(..) | SortType::Bucket(..) | SortType::Line(_) => SortUndo::Nothing,
+        }
+    }
+
+    fn revert(&mut self, step: &SortType<Tagged<T>>, undo: &SortUndo<T>) {
+        match (*step, undo) {
+            (SortType::Set(idx, _), SortUndo::Item(item)) => {
+                self.items[idx] = *item;
+                self.stats.writes -= 1;
+            }
+            // Swapping back moves the pivot back as well
+            (SortType::Swap(idx1, idx2), _) => {
+                self.items.swap(idx1, idx2);
+                self.annotations.apply(step);
+                self.stats.swaps -= 1;
+            }
+            (SortType::Compare(..), _) => self.stats.comparisons -= 1,
+            (SortType::AuxSet(idx, _), SortUndo::Aux(value)) => self.aux[idx] = *value,
+            (SortType::Digit(_), SortUndo::Digit(digit)) => self.digit = *digit,
+            (SortType::Run(..), SortUndo::Runs(at, runs)) => {
+                self.runs.splice(*at..at + 1, runs.iter().copied());
+            }
+            (_, SortUndo::Annotations(undo)) => self.annotations.revert(undo),
+            (SortType::Alloc(_) | SortType::Free(_), SortUndo::Memory(aux_memory, peak_aux_memory)) => {
+                self.stats.aux_memory = *aux_memory;
+                self.stats.peak_aux_memory = *peak_aux_memory;
+            }
+            _ => (),
         }
     }
 }
 
-// test
 #[derive(Clone, PartialEq)]
-pub struct Sorter {
-    pub data: Vec<i32>,
+pub struct Sorter<T: SortItem = i32> {
+    pub data: Vec<T>,
+    pub aux: Vec<usize>,
     algorithm: SortingAlgorithmEnum,
+    sort_key: SortKey,
+    pub tags: Vec<usize>,
+    /// Where each item ends up once sorted, by its original position
+    final_positions: Rc<Vec<usize>>,
     pub active_step: u32,
-    pub steps: VecDeque<SortType<i32>>,
+    pub steps: Rc<VecDeque<SortType<Tagged<T>>>>,
+    /// How many of the first n played steps are not silent, for every n
+    shown_steps: Rc<Vec<u32>>,
+    /// Tags of the equal keys left out of their original order once sorted
+    unstable_tags: Rc<Vec<(usize, usize)>>,
+    /// Pseudocode line of every step, in replay order
+    lines: Rc<Vec<usize>>,
+    pub pseudocode: Vec<&'static str>,
+    /// Why the chosen algorithm merge sorted the items instead, if it did
+    pub fallback: Option<String>,
+    timeline: Timeline<SortState<T>, SortType<Tagged<T>>>,
+    pub stats: SortStats,
+    pub active_stats: SortStats,
+    pub active_digit: Option<u32>,
+    pub active_runs: Vec<(usize, usize)>,
+    pub active_annotations: Annotations,
     steps_time: f32,
-    initial_data: Vec<i32>,
+    initial_data: Vec<T>,
     pub is_playing: bool,
 }
 
-impl Sorter {
-    pub fn new(sort_config: &SortConfigValues) -> Sorter {
-        let generation = get_new_generation(&sort_config.items_count);
-        let mut sorter = Self {
+pub fn generate_initial_data<T: SortItem>(sort_config: &SortConfigValues) -> Vec<T> {
+    let distribution = if sort_config.is_stability_demo() {
+        // Only a handful of distinct keys, so equal keys are everywhere
+        Distribution::FewUnique
+    } else {
+        Distribution::from_string(&sort_config.distribution).unwrap_or(Distribution::Shuffled)
+    };
+    match parse_custom_input(&sort_config.custom_input) {
+        Ok(values) if sort_config.is_custom_input() => values,
+        _ => {
+            let generation = get_new_generation(&sort_config.items_count, &distribution, sort_config.seed);
+            let items_count = generation.len();
+            generation
+                .into_iter()
+                .enumerate()
+                .map(|(idx, value)| T::from_generated(value, idx, items_count))
+                .collect()
+        }
+    }
+}
+
+/// Runs the configured algorithm without building a playback, returns its statistics and step count
+pub fn measure(sort_config: &SortConfigValues, data: &mut [i32]) -> (SortStats, usize) {
+    let mut steps = VecDeque::new();
+    let sort_key = SortKey::for_items(sort_config, data);
+    SortAlgorithm::new().do_sort(sort_config, sort_key, data, &mut steps);
+    let steps_count = steps.iter().filter(|step| !matches!(step, SortType::Line(_)) && !step.is_silent()).count();
+    (SortStats::from_steps(steps.iter().rev()), steps_count)
+}
+
+impl<T: SortItem> Sorter<T> {
+    pub fn new(sort_config: &SortConfigValues) -> Sorter<T> {
+        Sorter::from_data(generate_initial_data(sort_config), sort_config)
+    }
+
+    /// Sorter over a given array, so several algorithms can run on identical input
+    pub fn from_data(generation: Vec<T>, sort_config: &SortConfigValues) -> Sorter<T> {
+        let mut sorter = Sorter::unsorted(generation);
+        sorter.sort(sort_config);
+        sorter
+    }
+
+    /// Sorter replaying a recorded trace instead of running the algorithm
+    pub fn from_trace(trace: SortTrace<T>) -> Result<Sorter<T>, String> {
+        let len = trace.initial_data.len();
+        let fits = |idx: usize| idx < len;
+        for (position, step) in trace.steps.iter().enumerate() {
+            let valid = match *step {
+                SortType::Set(idx, item) => fits(idx) && fits(item.tag),
+                SortType::Swap(idx1, idx2) | SortType::Compare(idx1, idx2) => fits(idx1) && fits(idx2),
+                SortType::Bucket(idx, _) => fits(idx),
+                SortType::Run(lo, hi) | SortType::MarkSorted(lo, hi) | SortType::FocusRange(lo, hi) => lo <= hi && hi <= len,
+                SortType::MarkPivot(idx) => fits(idx),
+                SortType::HeapBoundary(end) => end <= len,
+                SortType::Alloc(_) | SortType::Free(_) | SortType::Digit(_) | SortType::AuxSet(..) => true,
+                SortType::Line(_) => false,
+            };
+            if !valid {
+                return Err(format!("Step {} ({}) does not fit {} items", position + 1, step, len));
+            }
+        }
+        if !trace.lines.is_empty() && trace.lines.len() != trace.steps.len() {
+            return Err(format!("Expected {} pseudocode lines, got {}", trace.steps.len(), trace.lines.len()));
+        }
+
+        let mut sorter = Sorter::unsorted(trace.initial_data);
+        sorter.load(&trace.config, trace.steps.into_iter().rev().collect(), trace.lines);
+        Ok(sorter)
+    }
+
+    pub fn to_trace(&self, sort_config: &SortConfigValues) -> SortTrace<T> {
+        SortTrace {
+            version: TRACE_VERSION,
+            config: sort_config.clone(),
+            initial_data: self.initial_data.clone(),
+            steps: self.steps.iter().rev().copied().collect(),
+            lines: self.lines.to_vec(),
+        }
+    }
+
+    fn unsorted(generation: Vec<T>) -> Sorter<T> {
+        Self {
             algorithm: SortingAlgorithmEnum::MergeSort,
+            sort_key: SortKey::default(),
             data: generation.clone(),
+            aux: Vec::new(),
+            tags: Vec::new(),
+            final_positions: Rc::new((0..generation.len()).collect()),
             active_step: 0,
-            steps: VecDeque::new(),
+            steps: Rc::new(VecDeque::new()),
+            shown_steps: Rc::new(vec![0]),
+            unstable_tags: Rc::new(Vec::new()),
+            lines: Rc::new(Vec::new()),
+            pseudocode: Vec::new(),
+            fallback: None,
+            timeline: Timeline::new(SortState::new(&generation, &VecDeque::new()), &VecDeque::new()),
+            stats: SortStats::default(),
+            active_stats: SortStats::default(),
+            active_digit: None,
+            active_runs: Vec::new(),
+            active_annotations: Annotations::default(),
             steps_time: 0.,
             initial_data: generation,
             is_playing: false,
-        };
-        sorter.generate(sort_config);
-        sorter
+        }
     }
 
     pub fn sort(&mut self, sort_config: &SortConfigValues) {
-        let mut data = self.initial_data.clone();
-        self.data = self.initial_data.clone();
-        self.steps = VecDeque::new();
-        self.active_step = 0;
+        let mut sort_config = sort_config.clone();
+        if !T::HAS_INT_KEY && needs_int_key(&sort_config.current_algorithm_name) {
+            sort_config.current_algorithm_name = String::from("merge_sort");
+        }
+        // Items are sorted with their original positions attached, which keeps stability observable
+        let mut data = Tagged::tag_all(&self.initial_data);
+        let mut recorded = VecDeque::new();
 
         let algorithm = SortAlgorithm::new();
-        algorithm.do_sort(sort_config, &mut data, &mut self.steps);
+        let sort_key = SortKey::for_items(&sort_config, &self.initial_data);
+        self.fallback = sort_config.key_span_fallback(sort_key.key_span(&self.initial_data));
+        algorithm.do_sort(&sort_config, sort_key, &mut data, &mut recorded);
+
+        // Line markers are folded into the steps after them, so they never show up as steps
+        let mut steps = VecDeque::with_capacity(recorded.len());
+        let mut lines = Vec::with_capacity(recorded.len());
+        let mut line = 0;
+        while let Some(step) = recorded.pop_back() {
+            match step {
+                SortType::Line(marked) => line = marked,
+                _ => {
+                    steps.push_front(step);
+                    lines.push(line);
+                }
+            }
+        }
+        self.load(&sort_config, steps, lines);
     }
 
-    fn _set_algorithm(&mut self, s: String) {
-        self.algorithm = SortingAlgorithmEnum::from_string(s).unwrap_or(SortingAlgorithmEnum::MergeSort);
+    fn load(&mut self, sort_config: &SortConfigValues, steps: VecDeque<SortType<Tagged<T>>>, lines: Vec<usize>) {
+        self.lines = Rc::new(lines);
+        self.sort_key = SortKey::for_items(sort_config, &self.initial_data);
+        self.algorithm = SortingAlgorithmEnum::from_string(sort_config.current_algorithm_name.clone())
+            .unwrap_or(SortingAlgorithmEnum::MergeSort);
+        let partition_scheme = PartitionScheme::from_string(&sort_config.partition_scheme)
+            .unwrap_or(PartitionScheme::Lomuto);
+        self.pseudocode = self.algorithm.pseudocode(partition_scheme);
+        // steps are stored newest first, so replay order is back to front
+        self.stats = SortStats::from_steps(steps.iter().rev());
+        self.timeline = Timeline::new(SortState::new(&self.initial_data, &steps), &steps);
+        self.timeline.seek(&steps, steps.len());
+        let mut final_positions = vec![0; self.initial_data.len()];
+        for (idx, item) in self.timeline.state().items.iter().enumerate() {
+            final_positions[item.tag] = idx;
+        }
+        self.final_positions = Rc::new(final_positions);
+        let final_keys: Vec<T> = self.timeline.state().items.iter().map(|item| item.key).collect();
+        let final_tags: Vec<usize> = self.timeline.state().items.iter().map(|item| item.tag).collect();
+        let unstable_tags = unstable_pairs(&final_keys, &final_tags)
+            .into_iter()
+            .map(|(idx1, idx2)| (final_tags[idx1], final_tags[idx2]))
+            .collect();
+        self.unstable_tags = Rc::new(unstable_tags);
+        let shown_steps = std::iter::once(0)
+            .chain(steps.iter().rev().scan(0, |shown, step| {
+                *shown += u32::from(!step.is_silent());
+                Some(*shown)
+            }))
+            .collect();
+        self.shown_steps = Rc::new(shown_steps);
+        self.steps = Rc::new(steps);
+        self.set_step(0);
+    }
+
+    /// Pairs of equal keys the algorithm leaves out of their original order, where they are now
+    pub fn unstable_pairs(&self) -> Vec<(usize, usize)> {
+        let mut positions = vec![0; self.tags.len()];
+        for (idx, &tag) in self.tags.iter().enumerate() {
+            positions[tag] = idx;
+        }
+        self.unstable_tags
+            .iter()
+            .map(|&(tag1, tag2)| {
+                let (idx1, idx2) = (positions[tag1], positions[tag2]);
+                (idx1.min(idx2), idx1.max(idx2))
+            })
+            .collect()
+    }
+
+    pub fn unstable_count(&self) -> usize {
+        self.unstable_tags.len()
+    }
+
+    /// The last write or swap played after step `from` and up to step `to`
+    pub fn last_move(&self, from: u32, to: u32) -> Option<SortType<T>> {
+        (from + 1..=to)
+            .rev()
+            .filter_map(|step| self.steps.len().checked_sub(step as usize).and_then(|idx| self.steps.get(idx)))
+            .find(|step| matches!(step, SortType::Set(..) | SortType::Swap(..)))
+            .map(|step| step.map(|item| item.key))
+    }
+
+    /// How many places each item is away from where the sort leaves it
+    pub fn displacements(&self) -> Vec<usize> {
+        self.tags
+            .iter()
+            .enumerate()
+            .map(|(idx, &tag)| self.final_positions[tag].abs_diff(idx))
+            .collect()
+    }
+
+    /// Smallest and largest bar heights of the run, the chart is scaled by them
+    pub fn value_range(&self) -> (f64, f64) {
+        let min = self.initial_data.iter().min().map_or(0., SortItem::magnitude);
+        let max = self.initial_data.iter().max().map_or(0., SortItem::magnitude);
+        (min, max)
+    }
+
+    /// Bar heights of the items as they are now
+    pub fn magnitudes(&self) -> Vec<f64> {
+        self.data.iter().map(SortItem::magnitude).collect()
+    }
+
+    /// Every item as text, in the order they are now
+    pub fn labels(&self) -> Vec<String> {
+        self.data.iter().map(ToString::to_string).collect()
+    }
+
+    /// Whether the items are in the configured order, items with equal keys may come either way
+    pub fn is_sorted(&self) -> bool {
+        self.data.windows(2).all(|pair| self.sort_key.compare(&pair[0], &pair[1], SortItem::int_key).is_le())
+    }
+
+    /// Pseudocode line of the step shown last, none before the first one
+    pub fn get_active_line(&self) -> Option<usize> {
+        (self.active_step as usize).checked_sub(1).and_then(|position| self.lines.get(position).copied())
     }
 
     fn reset(&mut self, sort_config: &SortConfigValues) {
@@ -138,13 +769,26 @@ impl Sorter {
         self.calculate_time(sort_config);
     }
 
+    // Whether the step played last at `step` is a silent one
+    fn is_silent_step(&self, step: u32) -> bool {
+        self.steps
+            .len()
+            .checked_sub(step as usize)
+            .and_then(|idx| self.steps.get(idx))
+            .is_some_and(|step| step.is_silent())
+    }
+
+    fn shown_steps_count(&self) -> u32 {
+        *self.shown_steps.last().unwrap_or(&0)
+    }
+
     pub fn play(&mut self, sort_config: &SortConfigValues) {
         self.is_playing = true;
         if self.active_step as usize == self.steps.len() {
             self.reset(sort_config);
             return;
         }
-        if self.is_playing == false && self.steps_time > 0. {
+        if !self.is_playing && self.steps_time > 0. {
             return;
         }
 
@@ -152,7 +796,7 @@ impl Sorter {
     }
 
     fn calculate_time(&mut self, sort_config: &SortConfigValues) {
-        self.steps_time = sort_config.time_overall as f32 / self.steps.len() as f32 * MS_IN_SECS;
+        self.steps_time = sort_config.time_overall as f32 / self.shown_steps_count() as f32 * MS_IN_SECS;
     }
 
     pub fn stop(&mut self) {
@@ -161,14 +805,12 @@ impl Sorter {
 
 
     pub fn generate(&mut self, sort_config: &SortConfigValues) {
-        self.data = get_new_generation(&sort_config.items_count);
-        self.steps = VecDeque::new();
-        self.initial_data = self.data.clone();
+        self.initial_data = generate_initial_data(sort_config);
         self.sort(sort_config);
     }
 }
 
-impl Playable<SortConfigValues, SortType<i32>, Vec<i32>> for Sorter {
+impl<T: SortItem> Playable<SortConfigValues, SortType<T>, Vec<T>> for Sorter<T> {
     fn reset(&mut self, sort_config: &SortConfigValues) {
         self.data = self.initial_data.clone();
         self.set_step(0);
@@ -181,7 +823,7 @@ impl Playable<SortConfigValues, SortType<i32>, Vec<i32>> for Sorter {
             self.reset(sort_config);
             return;
         }
-        if self.is_playing == false && self.steps_time > 0. {
+        if !self.is_playing && self.steps_time > 0. {
             return;
         }
 
@@ -198,26 +840,41 @@ impl Playable<SortConfigValues, SortType<i32>, Vec<i32>> for Sorter {
             // Clear interval when the end is reached.
             self.steps_time = 0.;
             self.is_playing = false;
-            return ();
+            return;
         } 
         let step_increment = (MAX_REFRESH_RATE / self.steps_time).ceil() as u32;
         let new_step_index = self.active_step + step_increment;
-        let new_step_index = if new_step_index >= max_steps {
+        let mut new_step_index = if new_step_index >= max_steps {
             max_steps
         } else {
             new_step_index
         };
-        self.data = self.get_output_by_step(new_step_index);
-        self.active_step = new_step_index;
+        // Playback never rests on a silent step, it shows up along with the next one
+        while new_step_index < max_steps && self.is_silent_step(new_step_index) {
+            new_step_index += 1;
+        }
+        // Nor does it stop for the silent steps trailing the last shown one
+        if self.shown_steps[new_step_index as usize] == self.shown_steps_count() {
+            new_step_index = max_steps;
+        }
+        self.set_step(new_step_index);
     }
 
     fn set_step(&mut self, step: u32) {
-        self.data = self.get_output_by_step(step);
+        self.timeline.seek(&self.steps, step as usize);
+        let state = self.timeline.state();
+        self.data = state.items.iter().map(|item| item.key).collect();
+        self.tags = state.items.iter().map(|item| item.tag).collect();
+        self.aux = state.aux.clone();
+        self.active_runs = state.runs.clone();
+        self.active_annotations = state.annotations.clone();
+        self.active_stats = state.stats;
+        self.active_digit = state.digit;
         self.active_step = step;
     }
     
     fn get_active_step_string(&self) -> String {
-        self.active_step.to_string()
+        self.shown_steps[self.active_step as usize].to_string()
     }
 
     fn tick_time(&self) -> u32 {
@@ -227,32 +884,439 @@ impl Playable<SortConfigValues, SortType<i32>, Vec<i32>> for Sorter {
         self.steps_time.max(MAX_REFRESH_RATE) as u32
     }
 
-    fn get_output_by_step(&mut self, step: u32) -> Vec<i32> {
-        let mut steps = self.steps.clone();
-        let mut data = self.initial_data.clone();
-        for _ in 0..step {
-            match steps.pop_back().unwrap() {
-                SortType::Set(index, val) => data[index] = val,
-                SortType::Swap(index1, index2) => {
-                    data.swap(index1, index2);
-                },
-            }
-        }
-        data
+    fn get_output_by_step(&mut self, step: u32) -> Vec<T> {
+        self.timeline.seek(&self.steps, step as usize);
+        self.timeline.state().items.iter().map(|item| item.key).collect()
     }
 
     fn get_steps_len_string(&self) -> String {
-        self.steps.len().to_string()
+        self.shown_steps_count().to_string()
     }
 
-    fn get_active_step_item(&self) -> SortType<i32> {
+    fn get_active_step_item(&self) -> SortType<T> {
         match self.steps.get(self.steps.len() - self.active_step as usize) {
-            Some(v) => *v,
+            Some(v) => v.map(|item| item.key),
             None => SortType::Swap(0, 0)
         }
     }
 
     fn calculate_time(&mut self, sort_config: &SortConfigValues) {
-        self.steps_time = sort_config.time_overall as f32 / self.steps.len() as f32 * MS_IN_SECS;
+        self.steps_time = sort_config.time_overall as f32 / self.shown_steps_count() as f32 * MS_IN_SECS;
+    }
+
+    fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    fn get_active_step(&self) -> u32 {
+        self.active_step
+    }
+
+    fn get_final_step(&self) -> u32 {
+        self.steps.len() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sorting_algorithms::bubble_sort::bubble_sort;
+    use crate::sorting_algorithms::merge_sort::merge_sort;
+    use crate::services::playable::PlaybackCommand;
+    use crate::services::trace::{from_json, to_json};
+
+    use super::*;
+
+    #[test]
+    fn test_sort_stats() {
+        let mut steps = VecDeque::new();
+        bubble_sort(&mut [3, 2, 1], &mut steps, &i32::cmp);
+        let stats = SortStats::from_steps(steps.iter().rev());
+        assert_eq!(stats.comparisons, 3);
+        assert_eq!(stats.swaps, 3);
+        assert_eq!(stats.writes, 0);
+        assert_eq!(stats.peak_aux_memory, 0);
+
+        let mut steps = VecDeque::new();
+        merge_sort(&mut [4, 3, 2, 1], &mut steps, &i32::cmp);
+        let stats = SortStats::from_steps(steps.iter().rev());
+        assert_eq!(stats.writes, 8);
+        assert_eq!(stats.aux_memory, 0);
+        assert!(stats.peak_aux_memory >= 4);
+    }
+
+    #[test]
+    fn test_measure_plays_steps_in_order() {
+        let mut config = SortConfigValues::new();
+        config.current_algorithm_name = "merge_sort".to_string();
+        let mut data = vec![7, 1, 6, 2, 5, 3, 4, 0];
+        let (stats, _) = measure(&config, &mut data.clone());
+
+        let mut steps = VecDeque::new();
+        merge_sort(&mut data, &mut steps, &i32::cmp);
+        assert_eq!(stats, SortStats::from_steps(steps.iter().rev()));
+        assert_eq!(stats.aux_memory, 0);
+        assert!(stats.peak_aux_memory >= 8);
+    }
+
+    #[test]
+    fn test_playback_never_rests_on_silent_steps() {
+        let mut config = SortConfigValues::new();
+        config.items_count = 20;
+        config.time_overall = 1000;
+        let mut sorter = Sorter::<i32>::new(&config);
+        let shown_steps = sorter.steps.iter().filter(|step| !step.is_silent()).count();
+        assert!(shown_steps < sorter.steps.len());
+        assert_eq!(sorter.get_steps_len_string(), shown_steps.to_string());
+
+        sorter.play(&config);
+        let mut frames = 0;
+        while sorter.is_playing {
+            sorter.tick();
+            frames += 1;
+            let at_end = sorter.active_step == sorter.steps.len() as u32;
+            assert!(at_end || !sorter.get_active_step_item().is_silent());
+        }
+        assert_eq!(sorter.active_step, sorter.steps.len() as u32);
+        assert_eq!(sorter.get_active_step_string(), shown_steps.to_string());
+        assert!(frames <= shown_steps + 1);
+    }
+
+    #[test]
+    fn test_last_move_skips_steps_that_move_nothing() {
+        let mut config = SortConfigValues::new();
+        config.input_mode = "custom".to_string();
+        config.custom_input = "2 1".to_string();
+        config.current_algorithm_name = "bubble_sort".to_string();
+        let sorter = Sorter::<i32>::new(&config);
+        let len = sorter.steps.len() as u32;
+        assert_eq!(sorter.last_move(0, len), Some(SortType::Swap(0, 1)));
+        assert_eq!(sorter.last_move(0, 1), None);
+        assert_eq!(sorter.last_move(2, len), None);
+        assert_eq!(sorter.last_move(len, 0), None);
+    }
+
+    #[test]
+    fn test_json_trace_round_trip_restores_playback() {
+        let mut config = SortConfigValues::new();
+        config.items_count = 30;
+        config.current_algorithm_name = "quick_sort".to_string();
+        config.partition_scheme = "hoare".to_string();
+        let mut sorter = Sorter::<i32>::new(&config);
+
+        let json = to_json(&sorter.to_trace(&config));
+        let trace: SortTrace = from_json(&json).unwrap();
+        assert_eq!(trace.config, config);
+        let mut imported = Sorter::from_trace(trace).unwrap();
+        assert_eq!(imported.steps, sorter.steps);
+        assert_eq!(imported.pseudocode, sorter.pseudocode);
+        for step in [sorter.steps.len() as u32 / 2, sorter.steps.len() as u32, 1] {
+            sorter.set_step(step);
+            imported.set_step(step);
+            assert_eq!((&imported.data, &imported.tags), (&sorter.data, &sorter.tags));
+            assert_eq!(imported.active_stats, sorter.active_stats);
+            assert_eq!(imported.get_active_line(), sorter.get_active_line());
+        }
+
+        let mut trace = sorter.to_trace(&config);
+        trace.steps.push(SortType::Swap(0, 30));
+        assert!(Sorter::from_trace(trace).is_err());
+    }
+
+    #[test]
+    fn test_every_algorithm_follows_order_and_key_mode() {
+        let mut config = SortConfigValues::new();
+        config.items_count = 40;
+        config.distribution = "few_unique".to_string();
+        for option in config.alg_options.clone() {
+            for (order, mode) in [("descending", "value"), ("ascending", "last_digit"), ("descending", "parity_then_value")] {
+                config.current_algorithm_name = option.value.clone();
+                config.sort_order = order.to_string();
+                config.key_mode = mode.to_string();
+                let mut sorter = Sorter::<i32>::new(&config);
+                let mut expected = sorter.initial_data.clone();
+                expected.sort();
+                sorter.set_step(sorter.steps.len() as u32);
+                assert!(sorter.is_sorted(), "{} {} {}: {:?}", option.value, order, mode, sorter.data);
+
+                let mut items = sorter.data.clone();
+                items.sort();
+                assert_eq!(items, expected);
+            }
+        }
+
+        config.current_algorithm_name = "counting_sort".to_string();
+        config.custom_input = "13 20 7 3 -7".to_string();
+        config.input_mode = "custom".to_string();
+        config.sort_order = "ascending".to_string();
+        config.key_mode = "last_digit".to_string();
+        let mut sorter = Sorter::<i32>::new(&config);
+        sorter.set_step(sorter.steps.len() as u32);
+        assert_eq!(sorter.data, vec![20, 13, 3, 7, -7]);
+    }
+
+    #[test]
+    fn test_displacements_reach_zero_at_the_end() {
+        let mut config = SortConfigValues::new();
+        config.input_mode = "custom".to_string();
+        config.custom_input = "3 1 2 2".to_string();
+        config.sort_order = "descending".to_string();
+        let mut sorter = Sorter::<i32>::new(&config);
+        assert_eq!(sorter.displacements(), vec![0, 2, 1, 1]);
+        sorter.set_step(sorter.steps.len() as u32);
+        assert_eq!(sorter.data, vec![3, 2, 2, 1]);
+        assert_eq!(sorter.displacements(), vec![0; 4]);
+    }
+
+    #[test]
+    fn test_annotations_mark_the_heap_and_the_finished_region() {
+        let mut config = SortConfigValues::new();
+        config.items_count = 30;
+        config.current_algorithm_name = "heap_sort".to_string();
+        let mut sorter = Sorter::<i32>::new(&config);
+        let len = sorter.steps.len() as u32;
+        for step in (0..=len).step_by(7) {
+            sorter.set_step(step);
+            let annotations = &sorter.active_annotations;
+            // Whatever is past the heap is the sorted tail, once the step marking it has played
+            let is_marking = matches!(sorter.get_active_step_item(), SortType::HeapBoundary(_));
+            if let Some(end) = annotations.heap_end.filter(|&end| end < 30 && !is_marking) {
+                assert_eq!(annotations.sorted, vec![(end, 30)], "step {}", step);
+                assert_eq!(sorter.data[end..], (end as i32 + 1..=30).collect::<Vec<i32>>()[..]);
+            }
+        }
+
+        for algorithm in ["quick_sort", "merge_sort", "heap_sort", "shell_sort"] {
+            config.current_algorithm_name = algorithm.to_string();
+            let mut sorter = Sorter::<i32>::new(&config);
+            sorter.set_step(sorter.steps.len() as u32);
+            assert_eq!(sorter.active_annotations.sorted, vec![(0, 30)], "{}", algorithm);
+            assert!(sorter.active_annotations.focus.is_some(), "{}", algorithm);
+            sorter.set_step(0);
+            assert_eq!(sorter.active_annotations, Annotations::default(), "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_pivot_mark_follows_swaps() {
+        let mut config = SortConfigValues::new();
+        config.input_mode = "custom".to_string();
+        config.custom_input = "2 9 4 7 1".to_string();
+        config.current_algorithm_name = "quick_sort".to_string();
+        config.pivot_choice = "first".to_string();
+        let mut sorter = Sorter::<i32>::new(&config);
+        let mut pivot_seen = false;
+        for step in 1..=sorter.steps.len() as u32 {
+            sorter.set_step(step);
+            if let Some(pivot) = sorter.active_annotations.pivot {
+                // The first pivot is the 2, wherever the partition moves it
+                if sorter.active_annotations.focus == Some((0, 5)) {
+                    assert_eq!(sorter.data[pivot], 2, "step {}", step);
+                    pivot_seen = true;
+                }
+            }
+        }
+        assert!(pivot_seen);
+    }
+
+    #[test]
+    fn test_tim_sort_reports_merged_runs() {
+        let mut config = SortConfigValues::new();
+        config.items_count = 100;
+        config.current_algorithm_name = "tim_sort".to_string();
+        let mut sorter = Sorter::<i32>::new(&config);
+        sorter.set_step(sorter.steps.len() as u32);
+        assert_eq!(sorter.active_runs, vec![(0, 100)]);
+        assert_eq!(sorter.data, (1..=100).collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_run() {
+        let mut config = SortConfigValues::new();
+        config.seed = 2024;
+        config.current_algorithm_name = "quick_sort".to_string();
+        config.pivot_choice = "random".to_string();
+        let first = Sorter::<i32>::new(&config);
+        let second = Sorter::<i32>::new(&config);
+        assert_eq!(first.data, second.data);
+        assert_eq!(first.steps, second.steps);
+
+        config.seed = 2025;
+        assert_ne!(first.data, Sorter::<i32>::new(&config).data);
+    }
+
+    #[test]
+    fn test_custom_input_replaces_generation() {
+        let mut config = SortConfigValues::new();
+        config.input_mode = "custom".to_string();
+        config.custom_input = "4, -2, 4, 0".to_string();
+        let mut sorter = Sorter::<i32>::new(&config);
+        assert_eq!(sorter.data, vec![4, -2, 4, 0]);
+        assert_eq!(sorter.value_range(), (-2., 4.));
+        sorter.set_step(sorter.steps.len() as u32);
+        assert_eq!(sorter.data, vec![-2, 0, 4, 4]);
+
+        config.custom_input = "4, x".to_string();
+        assert_eq!(Sorter::<i32>::new(&config).data.len(), config.items_count as usize);
+    }
+
+    #[test]
+    fn test_pigeonhole_sort_replays_aux_counts() {
+        let mut config = SortConfigValues::new();
+        config.items_count = 20;
+        config.current_algorithm_name = "pigeonhole_sort".to_string();
+        let mut sorter = Sorter::<i32>::new(&config);
+        assert_eq!(sorter.aux, vec![0; 20]);
+
+        // Once every item is scattered, right before the first one is written back, each hole holds one of them
+        let scattered = sorter.steps.iter().rev().position(|step| matches!(step, SortType::Set(..))).unwrap();
+        sorter.set_step(scattered as u32);
+        assert_eq!(sorter.aux, vec![1; 20]);
+
+        sorter.set_step(sorter.steps.len() as u32);
+        assert_eq!(sorter.aux, vec![0; 20]);
+        assert_eq!(sorter.data, (1..=20).collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn test_wide_custom_keys_fall_back_to_merge_sort() {
+        let mut config = SortConfigValues::new();
+        config.input_mode = "custom".to_string();
+        config.custom_input = "-2000000000 2000000000 5".to_string();
+        for algorithm in ["counting_sort", "pigeonhole_sort"] {
+            config.current_algorithm_name = algorithm.to_string();
+            assert!(config.key_span_warning().is_some(), "{}", algorithm);
+            let mut sorter = Sorter::<i32>::new(&config);
+            assert_eq!(sorter.fallback, config.key_span_warning());
+            sorter.set_step(sorter.steps.len() as u32);
+            assert_eq!(sorter.data, vec![-2000000000, 5, 2000000000]);
+        }
+
+        // Parity then value puts odd keys past the even ones, so their span about doubles
+        config.custom_input = "0 6000 6001".to_string();
+        assert_eq!(config.key_span_warning(), None);
+        config.key_mode = "parity_then_value".to_string();
+        assert!(config.key_span_warning().is_some());
+        config.current_algorithm_name = "merge_sort".to_string();
+        assert_eq!(config.key_span_warning(), None);
+
+        // Generated items are checked too, the config only knows the custom ones
+        config.input_mode = "random".to_string();
+        config.current_algorithm_name = "counting_sort".to_string();
+        config.items_count = 20_000;
+        assert!(Sorter::<i32>::new(&config).fallback.is_some());
+        config.items_count = 100;
+        assert_eq!(Sorter::<i32>::new(&config).fallback, None);
+    }
+
+    #[test]
+    fn test_stability_is_tracked_through_tags() {
+        let mut config = SortConfigValues::new();
+        let initial = vec![2, 1, 2, 1, 2, 1, 2, 1];
+        for (algorithm, is_stable) in [("merge_sort", true), ("counting_sort", true), ("heap_sort", false)] {
+            config.current_algorithm_name = algorithm.to_string();
+            let mut sorter = Sorter::from_data(initial.clone(), &config);
+            sorter.set_step(sorter.steps.len() as u32);
+            assert_eq!(sorter.data, vec![1, 1, 1, 1, 2, 2, 2, 2]);
+            assert_eq!(sorter.unstable_pairs().is_empty(), is_stable, "{}", algorithm);
+            // Worked out once from where the items end up, and followed back to the start
+            sorter.set_step(0);
+            assert_eq!(sorter.unstable_pairs().len(), sorter.unstable_count(), "{}", algorithm);
+            assert_eq!(sorter.unstable_count() == 0, is_stable, "{}", algorithm);
+            sorter.set_step(sorter.steps.len() as u32);
+            if is_stable {
+                assert_eq!(sorter.tags, vec![1, 3, 5, 7, 0, 2, 4, 6]);
+            }
+        }
+
+        config.input_mode = "stability".to_string();
+        let sorter = Sorter::<i32>::new(&config);
+        let mut keys = sorter.data.clone();
+        keys.sort();
+        keys.dedup();
+        assert!(keys.len() < sorter.data.len() / 2);
+    }
+
+    #[test]
+    fn test_seeking_backward_matches_fresh_replay() {
+        let mut config = SortConfigValues::new();
+        config.items_count = 300;
+        for algorithm in ["tim_sort", "pigeonhole_sort", "radix_sort_msd", "merge_sort", "quick_sort", "heap_sort"] {
+            config.current_algorithm_name = algorithm.to_string();
+            let mut sorter = Sorter::<i32>::new(&config);
+            let total = sorter.steps.len() as u32;
+            sorter.set_step(total);
+            for step in [total - 1, total / 2, total / 2 + 70, 3, 0] {
+                sorter.set_step(step);
+                let mut fresh = Sorter::from_data(sorter.initial_data.clone(), &config);
+                fresh.set_step(step);
+                assert_eq!(sorter.data, fresh.data, "{} at {}", algorithm, step);
+                assert_eq!(sorter.tags, fresh.tags, "{} at {}", algorithm, step);
+                assert_eq!(sorter.aux, fresh.aux, "{} at {}", algorithm, step);
+                assert_eq!(sorter.active_runs, fresh.active_runs, "{} at {}", algorithm, step);
+                assert_eq!(sorter.active_stats, fresh.active_stats, "{} at {}", algorithm, step);
+                assert_eq!(sorter.active_digit, fresh.active_digit, "{} at {}", algorithm, step);
+                assert_eq!(sorter.active_annotations, fresh.active_annotations, "{} at {}", algorithm, step);
+            }
+
+            // Runs and annotations are taken back step by step from their undo records
+            let mut marks = |step| {
+                sorter.set_step(step);
+                (sorter.active_runs.clone(), sorter.active_annotations.clone())
+            };
+            let forward: Vec<_> = (0..=total).map(&mut marks).collect();
+            for step in (0..=total).rev() {
+                assert_eq!(marks(step), forward[step as usize], "{} at {}", algorithm, step);
+            }
+        }
+    }
+
+    #[test]
+    fn test_playback_commands_step_and_jump() {
+        let config = SortConfigValues::new();
+        let mut sorter = Sorter::<i32>::new(&config);
+        let final_step = sorter.steps.len() as u32;
+
+        sorter.run_command(PlaybackCommand::StepBack, &config);
+        assert_eq!(sorter.active_step, 0);
+        sorter.run_command(PlaybackCommand::StepForward, &config);
+        sorter.run_command(PlaybackCommand::StepForward, &config);
+        assert_eq!(sorter.active_step, 2);
+
+        sorter.run_command(PlaybackCommand::TogglePlay, &config);
+        assert!(sorter.is_playing);
+        sorter.run_command(PlaybackCommand::JumpToEnd, &config);
+        assert!(!sorter.is_playing);
+        assert_eq!(sorter.active_step, final_step);
+        sorter.run_command(PlaybackCommand::StepForward, &config);
+        assert_eq!(sorter.active_step, final_step);
+
+        sorter.run_command(PlaybackCommand::JumpToStart, &config);
+        assert_eq!(sorter.data, sorter.initial_data);
+        assert_eq!(PlaybackCommand::from_key("Home"), Ok(PlaybackCommand::JumpToStart));
+        assert!(PlaybackCommand::from_key("a").is_err());
+    }
+
+    #[test]
+    fn test_every_step_points_into_its_pseudocode() {
+        let mut config = SortConfigValues::new();
+        config.items_count = 60;
+        let algorithms: Vec<String> = config.algorithm_options().iter().map(|option| option.value.clone()).collect();
+        for algorithm in algorithms {
+            config.current_algorithm_name = algorithm.clone();
+            let sorter = Sorter::<i32>::new(&config);
+            assert_eq!(sorter.lines.len(), sorter.steps.len(), "{}", algorithm);
+            assert!(sorter.steps.iter().all(|step| !matches!(step, SortType::Line(_))), "{}", algorithm);
+            assert!(sorter.lines.iter().all(|&line| line < sorter.pseudocode.len()), "{}", algorithm);
+        }
+
+        config.current_algorithm_name = "bubble_sort".to_string();
+        config.input_mode = "custom".to_string();
+        config.custom_input = "2, 1".to_string();
+        let mut sorter = Sorter::<i32>::new(&config);
+        assert_eq!(sorter.get_active_line(), None);
+        sorter.set_step(1);
+        assert_eq!(sorter.pseudocode[sorter.get_active_line().unwrap()], "        if a[j] > a[j + 1]");
+        sorter.set_step(2);
+        assert_eq!(sorter.get_active_line(), Some(3));
     }
 }
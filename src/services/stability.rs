@@ -0,0 +1,101 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::sort_item::SortItem;
+
+/// An item carrying its original position, ordered by the key alone
+///
+/// Equal keys compare as equal whatever their tags are, so the tags reveal
+/// whether an algorithm kept them in the order they started in.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(from = "(T, usize)", into = "(T, usize)", bound(serialize = "T: Serialize + Clone", deserialize = "T: Deserialize<'de>"))]
+pub struct Tagged<T = i32> {
+    pub key: T,
+    pub tag: usize,
+}
+
+impl<T: Copy> Tagged<T> {
+    pub fn tag_all(keys: &[T]) -> Vec<Tagged<T>> {
+        keys.iter()
+            .enumerate()
+            .map(|(tag, &key)| Tagged { key, tag })
+            .collect()
+    }
+}
+
+impl<T> From<(T, usize)> for Tagged<T> {
+    fn from((key, tag): (T, usize)) -> Tagged<T> {
+        Tagged { key, tag }
+    }
+}
+
+impl<T> From<Tagged<T>> for (T, usize) {
+    fn from(item: Tagged<T>) -> (T, usize) {
+        (item.key, item.tag)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Tagged<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<T: Eq> Eq for Tagged<T> {}
+
+impl<T: Ord> PartialOrd for Tagged<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Tagged<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+// Lets the counting and radix sorts run on any item with an integer key
+impl<T: SortItem> From<Tagged<T>> for i64 {
+    fn from(item: Tagged<T>) -> i64 {
+        item.key.int_key()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Tagged<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.key)
+    }
+}
+
+/// Index pairs holding equal keys whose original order got reversed
+///
+/// Ordered by key and then tag, each item sits next to the equal one that started
+/// right after it, so only those neighbours are checked.
+pub fn unstable_pairs<T: Ord>(keys: &[T], tags: &[usize]) -> Vec<(usize, usize)> {
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    order.sort_unstable_by(|&idx1, &idx2| keys[idx1].cmp(&keys[idx2]).then(tags[idx1].cmp(&tags[idx2])));
+    order
+        .windows(2)
+        .filter(|pair| keys[pair[0]] == keys[pair[1]] && pair[0] > pair[1])
+        .map(|pair| (pair[1], pair[0]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unstable_pairs_only_report_reordered_equal_keys() {
+        let keys = vec![1, 2, 2, 3, 3];
+        let tags = vec![0, 4, 1, 2, 3];
+        assert_eq!(unstable_pairs(&keys, &tags), vec![(1, 2)]);
+        assert!(unstable_pairs(&keys, &[0, 1, 2, 4, 3]).len() == 1);
+        assert!(unstable_pairs(&keys, &[0, 1, 2, 3, 4]).is_empty());
+        // Each equal key is only tied to the one that started right after it, wherever they are
+        assert_eq!(unstable_pairs(&[7, 1, 7, 7], &[3, 0, 2, 1]), vec![(2, 3), (0, 2)]);
+    }
+}
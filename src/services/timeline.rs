@@ -0,0 +1,176 @@
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+// Keyframes never get denser than this, short traces just replay from the start
+const MIN_KEYFRAME_INTERVAL: usize = 64;
+// Upper bound on stored snapshots, long traces space them further apart instead
+const MAX_KEYFRAMES: usize = 256;
+
+/// A playback state that steps can be applied to and taken back from
+pub trait Replay<Step>: Clone {
+    /// What a step overwrote, enough to revert it
+    type Undo: Clone + PartialEq + Debug;
+
+    fn apply(&mut self, step: &Step) -> Self::Undo;
+    fn revert(&mut self, step: &Step, undo: &Self::Undo);
+}
+
+/// Seekable playback over a step trace
+///
+/// Snapshots are taken every `interval` steps and each step remembers what it
+/// overwrote. Any seek therefore costs at most one interval of steps, whether it
+/// goes forward from the current position, backward through the undo records,
+/// or forward again from the closest snapshot.
+#[derive(Clone, Debug)]
+pub struct Timeline<State: Replay<Step>, Step> {
+    keyframes: Rc<Vec<State>>,
+    undo: Rc<Vec<State::Undo>>,
+    interval: usize,
+    state: State,
+    position: usize,
+}
+
+// Timelines over the same recorded trace share their keyframes and undo records, so
+// comparing those by pointer and then the position is enough
+impl<State: Replay<Step>, Step> PartialEq for Timeline<State, Step> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.keyframes, &other.keyframes)
+            && Rc::ptr_eq(&self.undo, &other.undo)
+            && self.position == other.position
+    }
+}
+
+// Traces are stored newest first, so the step applied n-th sits n places from the back
+fn step_at<Step>(steps: &VecDeque<Step>, position: usize) -> &Step {
+    &steps[steps.len() - 1 - position]
+}
+
+impl<State: Replay<Step>, Step> Timeline<State, Step> {
+    pub fn new(initial: State, steps: &VecDeque<Step>) -> Self {
+        let interval = MIN_KEYFRAME_INTERVAL.max(steps.len().div_ceil(MAX_KEYFRAMES));
+        let mut keyframes = Vec::with_capacity(steps.len() / interval + 1);
+        let mut undo = Vec::with_capacity(steps.len());
+        let mut state = initial.clone();
+        for (position, step) in steps.iter().rev().enumerate() {
+            if position % interval == 0 {
+                keyframes.push(state.clone());
+            }
+            undo.push(state.apply(step));
+        }
+        // A seek to the very end of a trace a whole number of intervals long loads a keyframe there
+        if steps.len().is_multiple_of(interval) {
+            keyframes.push(state);
+        }
+        Self {
+            keyframes: Rc::new(keyframes),
+            undo: Rc::new(undo),
+            interval,
+            state: initial,
+            position: 0,
+        }
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Moves the state to right after `target` steps, clamped to the trace length
+    pub fn seek(&mut self, steps: &VecDeque<Step>, target: usize) {
+        let target = target.min(self.undo.len());
+        let from_keyframe = target % self.interval;
+        if target >= self.position {
+            if target - self.position > from_keyframe {
+                self.load_keyframe(target);
+            }
+            self.forward(steps, target);
+        } else if self.position - target <= from_keyframe {
+            self.backward(steps, target);
+        } else {
+            self.load_keyframe(target);
+            self.forward(steps, target);
+        }
+    }
+
+    fn load_keyframe(&mut self, target: usize) {
+        let keyframe = target / self.interval;
+        self.state = self.keyframes[keyframe].clone();
+        self.position = keyframe * self.interval;
+    }
+
+    fn forward(&mut self, steps: &VecDeque<Step>, target: usize) {
+        while self.position < target {
+            self.state.apply(step_at(steps, self.position));
+            self.position += 1;
+        }
+    }
+
+    fn backward(&mut self, steps: &VecDeque<Step>, target: usize) {
+        while self.position > target {
+            self.position -= 1;
+            self.state.revert(step_at(steps, self.position), &self.undo[self.position]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, PartialEq, Debug)]
+    struct Counter(Vec<u32>);
+
+    impl Replay<(usize, u32)> for Counter {
+        type Undo = u32;
+
+        fn apply(&mut self, &(idx, value): &(usize, u32)) -> u32 {
+            std::mem::replace(&mut self.0[idx], value)
+        }
+
+        fn revert(&mut self, &(idx, _): &(usize, u32), undo: &u32) {
+            self.0[idx] = *undo;
+        }
+    }
+
+    #[test]
+    fn test_seeking_matches_replay_from_start() {
+        let steps: VecDeque<(usize, u32)> = (0..1000u32).map(|value| ((value as usize * 7) % 10, value)).collect();
+        let replay_to = |target: usize| {
+            let mut state = Counter(vec![0; 10]);
+            for step in steps.iter().rev().take(target) {
+                state.apply(step);
+            }
+            state
+        };
+
+        let mut timeline = Timeline::new(Counter(vec![0; 10]), &steps);
+        for target in [0, 1, 500, 499, 63, 64, 65, 1000, 999, 0, 2000, 130, 10] {
+            timeline.seek(&steps, target);
+            assert_eq!(timeline.state(), &replay_to(target.min(1000)), "seek to {}", target);
+        }
+
+        // Ends right on a keyframe
+        let steps: VecDeque<(usize, u32)> = steps.into_iter().skip(1000 - 128).collect();
+        let mut timeline = Timeline::new(Counter(vec![0; 10]), &steps);
+        timeline.seek(&steps, 128);
+        let mut state = Counter(vec![0; 10]);
+        for step in steps.iter().rev() {
+            state.apply(step);
+        }
+        assert_eq!(timeline.state(), &state);
+    }
+
+    #[test]
+    fn test_timelines_compare_by_trace_and_position() {
+        let steps: VecDeque<(usize, u32)> = (0..10u32).map(|value| (value as usize % 3, value)).collect();
+        let mut timeline = Timeline::new(Counter(vec![0; 3]), &steps);
+        let mut copy = timeline.clone();
+        assert!(timeline == copy);
+        copy.seek(&steps, 4);
+        assert!(timeline != copy);
+        timeline.seek(&steps, 4);
+        assert!(timeline == copy);
+        // Same steps, but recorded separately
+        assert!(Timeline::new(Counter(vec![0; 3]), &steps) != Timeline::new(Counter(vec![0; 3]), &steps));
+    }
+}
@@ -0,0 +1,153 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::config::maze_config::MazeConfigValues;
+use crate::config::sort_config::SortConfigValues;
+
+use super::maze_generator::{Cell, Coords};
+use super::mazer::MazeStep;
+use super::sort_item::SortItem;
+use super::sorter::SortType;
+use super::stability::Tagged;
+
+/// Bumped whenever a trace document changes shape, older documents are refused
+pub const TRACE_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TraceFormat {
+    Json,
+    Csv,
+}
+
+impl TraceFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TraceFormat::Json => "json",
+            TraceFormat::Csv => "csv",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            TraceFormat::Json => "application/json",
+            TraceFormat::Csv => "text/csv",
+        }
+    }
+}
+
+/// A recorded sort run, enough to replay it without running the algorithm again
+///
+/// Steps are listed in replay order, and each item is written as `[key, tag]`
+/// where the tag is the position the item started at.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(bound = "T: SortItem")]
+pub struct SortTrace<T = i32> {
+    pub version: u32,
+    pub config: SortConfigValues,
+    pub initial_data: Vec<T>,
+    pub steps: Vec<SortType<Tagged<T>>>,
+    /// Pseudocode line of every step, may be left out by hand-written traces
+    #[serde(default)]
+    pub lines: Vec<usize>,
+}
+
+/// A recorded maze solve, the maze is stored as it was before the solver ran
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct MazeTrace {
+    pub version: u32,
+    pub config: MazeConfigValues,
+    pub cells: Vec<Vec<Cell>>,
+    pub steps: Vec<MazeStep>,
+    #[serde(default)]
+    pub path: Vec<(usize, usize)>,
+}
+
+#[derive(Deserialize)]
+struct Versioned {
+    version: u32,
+}
+
+pub fn to_json<T: Serialize>(trace: &T) -> String {
+    serde_json::to_string_pretty(trace).unwrap()
+}
+
+pub fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, String> {
+    let Versioned { version } = serde_json::from_str(json).map_err(|err| format!("Not a trace: {}", err))?;
+    if version != TRACE_VERSION {
+        return Err(format!("Unsupported trace version {}, expected {}", version, TRACE_VERSION));
+    }
+    serde_json::from_str(json).map_err(|err| format!("Invalid trace: {}", err))
+}
+
+impl<T: SortItem> SortTrace<T> {
+    /// One row per step, columns that do not apply to a step are left empty
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("step,kind,first,second,value,tag,line\n");
+        for (position, step) in self.steps.iter().enumerate() {
+            let (kind, first, second, item) = match *step {
+                SortType::Set(idx, item) => ("set", Some(idx), None, Some(item)),
+                SortType::Swap(idx1, idx2) => ("swap", Some(idx1), Some(idx2), None),
+                SortType::Compare(idx1, idx2) => ("compare", Some(idx1), Some(idx2), None),
+                SortType::Alloc(size) => ("alloc", Some(size), None, None),
+                SortType::Free(size) => ("free", Some(size), None, None),
+                SortType::Bucket(idx, bucket) => ("bucket", Some(idx), Some(bucket), None),
+                SortType::Digit(digit) => ("digit", Some(digit as usize), None, None),
+                SortType::AuxSet(idx, value) => ("aux_set", Some(idx), Some(value), None),
+                SortType::Run(lo, hi) => ("run", Some(lo), Some(hi), None),
+                SortType::MarkPivot(idx) => ("mark_pivot", Some(idx), None, None),
+                SortType::MarkSorted(lo, hi) => ("mark_sorted", Some(lo), Some(hi), None),
+                SortType::FocusRange(lo, hi) => ("focus_range", Some(lo), Some(hi), None),
+                SortType::HeapBoundary(end) => ("heap_boundary", Some(end), None, None),
+                SortType::Line(line) => ("line", Some(line), None, None),
+            };
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                position + 1,
+                kind,
+                column(first),
+                column(second),
+                column(item.map(|item| item.key)),
+                column(item.map(|item| item.tag)),
+                column(self.lines.get(position)),
+            ));
+        }
+        csv
+    }
+}
+
+impl MazeTrace {
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("step,x,y,cell,line\n");
+        for (position, step) in self.steps.iter().enumerate() {
+            let MazeStep { coords: Coords { x, y }, cell_type, line } = *step;
+            csv.push_str(&format!("{},{},{},{},{}\n", position + 1, x, y, cell_type.as_name(), line));
+        }
+        csv
+    }
+}
+
+fn column<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_other_versions_are_refused() {
+        let trace = SortTrace {
+            version: TRACE_VERSION + 1,
+            config: SortConfigValues::new(),
+            initial_data: vec![2, 1],
+            steps: vec![SortType::Swap(0, 1)],
+            lines: vec![],
+        };
+        assert!(from_json::<SortTrace>(&to_json(&trace)).is_err());
+        assert!(from_json::<SortTrace>("[1, 2]").is_err());
+
+        let trace = SortTrace { version: TRACE_VERSION, ..trace };
+        assert_eq!(from_json::<SortTrace>(&to_json(&trace)), Ok(trace.clone()));
+        assert_eq!(trace.to_csv(), "step,kind,first,second,value,tag,line\n1,swap,0,1,,,\n");
+    }
+}
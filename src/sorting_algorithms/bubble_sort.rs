@@ -2,18 +2,30 @@ use std::collections::VecDeque;
 
 use crate::services::sorter::SortType;
 
-pub fn bubble_sort<T: Copy + Clone + Ord>(items: &mut Vec<T>, steps: &mut VecDeque<SortType<T>>) {
-    _bubble_sort(items, steps)
+use super::Comparator;
+
+pub const PSEUDOCODE: &[&str] = &[
+    "for i in 0..n",
+    "    for j in 0..n - i - 1",
+    "        if a[j] > a[j + 1]",
+    "            swap a[j] and a[j + 1]",
+];
+
+pub fn bubble_sort<T: Copy>(items: &mut [T], steps: &mut VecDeque<SortType<T>>, cmp: &Comparator<T>) {
+    _bubble_sort(items, steps, cmp)
 }
 
-fn _bubble_sort<T: Ord>(arr: &mut Vec<T>, steps: &mut VecDeque<SortType<T>>) {
+fn _bubble_sort<T>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, cmp: &Comparator<T>) {
     let n = arr.len();
     
     for i in 0..n {
         for j in 0..n - i - 1 {
-            if arr[j] > arr[j + 1] {
+            steps.push_front(SortType::Line(2));
+            steps.push_front(SortType::Compare(j, j + 1));
+            if cmp(&arr[j], &arr[j + 1]).is_gt() {
                 // Swap arr[j] and arr[j + 1]
                 arr.swap(j, j + 1);
+                steps.push_front(SortType::Line(3));
                 steps.push_front(SortType::Swap(j, j + 1));
             }
         }
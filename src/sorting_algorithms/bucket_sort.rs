@@ -0,0 +1,59 @@
+use std::collections::VecDeque;
+
+use crate::services::sorter::SortType;
+
+use super::insertion_sort::insertion_sort;
+use super::{sort_subrange, KeyFn};
+
+pub const PSEUDOCODE: &[&str] = &[
+    "k = ceil(sqrt(n)) buckets over min..=max",
+    "allocate the buckets",
+    "for each item x",
+    "    append x to bucket (x - min) * k / (max - min + 1)",
+    "start = 0",
+    "for each bucket b in order",
+    "    copy b into a[start..start + len(b)]",
+    "    insertion sort a[start..start + len(b)], start += len(b)",
+    "free the buckets",
+];
+
+pub fn bucket_sort<T: Copy>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, key: &KeyFn<T>) {
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+
+    let min = arr.iter().map(key).min().unwrap();
+    let max = arr.iter().map(key).max().unwrap();
+    let buckets_count = (len as f64).sqrt().ceil() as usize;
+    let range = max - min + 1;
+
+    // Scatter every item into the bucket covering its value range
+    let mut buckets: Vec<Vec<T>> = vec![Vec::new(); buckets_count];
+    steps.push_front(SortType::Line(1));
+    steps.push_front(SortType::Alloc(len));
+    for (idx, &item) in arr.iter().enumerate() {
+        let bucket = ((key(&item) - min) * buckets_count as i64 / range) as usize;
+        buckets[bucket].push(item);
+        steps.push_front(SortType::Line(3));
+        steps.push_front(SortType::Bucket(idx, bucket));
+    }
+
+    // Gather buckets back in order and sort each of them in place
+    let cmp = |a: &T, b: &T| key(a).cmp(&key(b));
+    let mut start = 0;
+    for bucket in buckets {
+        let end = start + bucket.len();
+        for (offset, item) in bucket.into_iter().enumerate() {
+            arr[start + offset] = item;
+            steps.push_front(SortType::Line(6));
+            steps.push_front(SortType::Set(start + offset, item));
+        }
+
+        steps.push_front(SortType::Line(7));
+        sort_subrange(arr, steps, start, end, insertion_sort, &cmp);
+        start = end;
+    }
+    steps.push_front(SortType::Line(8));
+    steps.push_front(SortType::Free(len));
+}
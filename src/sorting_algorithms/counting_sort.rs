@@ -0,0 +1,89 @@
+use std::collections::VecDeque;
+
+use crate::services::sorter::SortType;
+
+use super::merge_sort::merge_sort;
+use super::{key_span, without_lines, KeyFn, MAX_KEY_SPAN};
+
+pub const PSEUDOCODE: &[&str] = &[
+    "count = zeros over min..=max",
+    "for each item x",
+    "    count[x] += 1",
+    "for k in min + 1..=max",
+    "    count[k] += count[k - 1]",
+    "output = new array of n",
+    "for each item x from the end",
+    "    count[x] -= 1, output[count[x]] = x",
+    "copy output into a",
+    "if max - min is too large: merge sort a by key instead",
+];
+
+pub fn counting_sort<T: Copy>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, key: &KeyFn<T>) {
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+
+    if key_span(arr, key) > MAX_KEY_SPAN {
+        // A slot per key would not fit in memory
+        let cmp = |a: &T, b: &T| key(a).cmp(&key(b));
+        steps.push_front(SortType::Line(9));
+        without_lines(steps, |steps| merge_sort(arr, steps, &cmp));
+        return;
+    }
+
+    let min = arr.iter().map(key).min().unwrap();
+    let max = arr.iter().map(key).max().unwrap();
+    let slot = |item: T| (key(&item) - min) as usize;
+    let range = (max - min + 1) as usize;
+
+    // Count the occurrences of every key
+    let mut counts = vec![0; range];
+    steps.push_front(SortType::Line(0));
+    steps.push_front(SortType::Alloc(range));
+    for &item in arr.iter() {
+        counts[slot(item)] += 1;
+        steps.push_front(SortType::Line(2));
+        steps.push_front(SortType::AuxSet(slot(item), counts[slot(item)]));
+    }
+
+    // Turn counts into the positions right after the last item of every key
+    for idx in 1..range {
+        counts[idx] += counts[idx - 1];
+        steps.push_front(SortType::Line(4));
+        steps.push_front(SortType::AuxSet(idx, counts[idx]));
+    }
+
+    // Walk the input backwards so equal keys keep their order
+    let mut output = arr.to_vec();
+    steps.push_front(SortType::Line(5));
+    steps.push_front(SortType::Alloc(len));
+    for &item in arr.iter().rev() {
+        counts[slot(item)] -= 1;
+        output[counts[slot(item)]] = item;
+        steps.push_front(SortType::Line(7));
+        steps.push_front(SortType::AuxSet(slot(item), counts[slot(item)]));
+    }
+
+    for (idx, item) in output.into_iter().enumerate() {
+        arr[idx] = item;
+        steps.push_front(SortType::Line(8));
+        steps.push_front(SortType::Set(idx, item));
+    }
+    steps.push_front(SortType::Free(range + len));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::stability::Tagged;
+
+    use super::*;
+
+    #[test]
+    fn test_counting_sort_is_stable_and_sorted() {
+        let mut items = Tagged::tag_all(&[3, -1, 3, 0, 2, -1, 5]);
+        counting_sort(&mut items, &mut VecDeque::new(), &|item| item.key as i64);
+        let sorted: Vec<(i32, usize)> = items.iter().map(|item| (item.key, item.tag)).collect();
+        assert_eq!(sorted, vec![(-1, 1), (-1, 5), (0, 3), (2, 4), (3, 0), (3, 2), (5, 6)]);
+    }
+}
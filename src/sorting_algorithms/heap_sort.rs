@@ -2,42 +2,77 @@ use std::collections::VecDeque;
 
 use crate::services::sorter::SortType;
 
-fn heapify<T: Ord>(arr: &mut [T], n: usize, i: usize, steps: &mut VecDeque<SortType<T>>) {
+use super::Comparator;
+
+pub const PSEUDOCODE: &[&str] = &[
+    "heapify(a, n, i):",
+    "    largest = i",
+    "    if left(i) < n and a[left(i)] > a[largest]: largest = left(i)",
+    "    if right(i) < n and a[right(i)] > a[largest]: largest = right(i)",
+    "    if largest != i",
+    "        swap a[i] and a[largest]",
+    "        heapify(a, n, largest)",
+    "",
+    "for i in (0..n / 2).rev(): heapify(a, n, i)",
+    "for i in (0..n).rev()",
+    "    swap a[0] and a[i]",
+    "    heapify(a, i, 0)",
+];
+
+fn heapify<T>(arr: &mut [T], n: usize, i: usize, steps: &mut VecDeque<SortType<T>>, cmp: &Comparator<T>) {
     let mut largest = i;
     let left_child = 2 * i + 1;
     let right_child = 2 * i + 2;
 
-    if left_child < n && arr[left_child] > arr[largest] {
-        largest = left_child;
+    if left_child < n {
+        steps.push_front(SortType::Line(2));
+        steps.push_front(SortType::Compare(left_child, largest));
+        if cmp(&arr[left_child], &arr[largest]).is_gt() {
+            largest = left_child;
+        }
     }
 
-    if right_child < n && arr[right_child] > arr[largest] {
-        largest = right_child;
+    if right_child < n {
+        steps.push_front(SortType::Line(3));
+        steps.push_front(SortType::Compare(right_child, largest));
+        if cmp(&arr[right_child], &arr[largest]).is_gt() {
+            largest = right_child;
+        }
     }
 
     if largest != i {
         arr.swap(i, largest);
+        steps.push_front(SortType::Line(5));
         steps.push_front(SortType::Swap(i, largest));
-        heapify(arr, n, largest, steps);
+        heapify(arr, n, largest, steps, cmp);
     }
 }
 
-fn build_max_heap<T: Ord>(arr: &mut Vec<T>, steps: &mut VecDeque<SortType<T>>) {
+fn build_max_heap<T>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, cmp: &Comparator<T>) {
     let n = arr.len();
     for i in (0..n / 2).rev() {
-        heapify(arr, n, i, steps);
+        heapify(arr, n, i, steps, cmp);
     }
 }
 
-pub fn heap_sort<T: Copy + Clone + Ord>(arr: &mut Vec<T>, steps: &mut VecDeque<SortType<T>>) {
+pub fn heap_sort<T: Copy>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, cmp: &Comparator<T>) {
     let n = arr.len();
+    if n == 0 {
+        return;
+    }
 
-    build_max_heap(arr, steps);
+    steps.push_front(SortType::FocusRange(0, n));
+    build_max_heap(arr, steps, cmp);
+    steps.push_front(SortType::HeapBoundary(n));
 
+    // The largest item of the heap moves to its end, so the sorted tail grows as the heap shrinks
     for i in (0..n).rev() {
         arr.swap(0, i);
+        steps.push_front(SortType::Line(10));
         steps.push_front(SortType::Swap(0, i));
-        heapify(arr, i, 0, steps);
+        steps.push_front(SortType::HeapBoundary(i));
+        steps.push_front(SortType::MarkSorted(i, i + 1));
+        heapify(arr, i, 0, steps, cmp);
     }
 }
 
@@ -2,14 +2,30 @@ use std::collections::VecDeque;
 
 use crate::services::sorter::SortType;
 
-pub fn insertion_sort<T: Copy + Clone + Ord>(arr: &mut Vec<T>, steps: &mut VecDeque<SortType<T>>) {
+use super::Comparator;
+
+pub const PSEUDOCODE: &[&str] = &[
+    "for i in 1..n",
+    "    j = i",
+    "    while j > 0 and a[j] < a[j - 1]",
+    "        swap a[j] and a[j - 1]",
+    "        j = j - 1",
+];
+
+pub fn insertion_sort<T: Copy>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, cmp: &Comparator<T>) {
     let len = arr.len();
 
     for i in 1..len {
         let mut j = i;
 
-        while j > 0 && arr[j] < arr[j - 1] {
+        while j > 0 {
+            steps.push_front(SortType::Line(2));
+            steps.push_front(SortType::Compare(j, j - 1));
+            if cmp(&arr[j], &arr[j - 1]).is_ge() {
+                break;
+            }
             arr.swap(j, j - 1);
+            steps.push_front(SortType::Line(3));
             steps.push_front(SortType::Swap(j, j - 1));
             j -= 1;
         }
@@ -0,0 +1,57 @@
+use std::collections::VecDeque;
+
+use crate::services::sorter::SortType;
+
+use super::heap_sort::heap_sort;
+use super::insertion_sort::insertion_sort;
+use super::quick_sort::{choose_pivot, partition_lomuto, FixedPivot};
+use super::{sort_subrange, without_lines, Comparator};
+
+const INSERTION_SORT_THRESHOLD: usize = 16;
+
+pub const PSEUDOCODE: &[&str] = &[
+    "intro_sort(a, lo, hi, depth):",
+    "    if hi - lo <= 16: insertion sort a[lo..hi], return",
+    "    if depth == 0: heap sort a[lo..hi], return",
+    "    p = median of a[lo], a[mid], a[hi - 1]",
+    "    p = partition a[lo..hi] around p (Lomuto)",
+    "    intro_sort(a, lo, p, depth - 1)",
+    "    intro_sort(a, p + 1, hi, depth - 1)",
+];
+
+pub fn intro_sort<T: Copy>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, cmp: &Comparator<T>) {
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+    let depth_limit = 2 * len.ilog2();
+    _intro_sort(arr, steps, cmp, 0, len, depth_limit);
+}
+
+fn _intro_sort<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    lo: usize,
+    hi: usize,
+    depth_limit: u32,
+) {
+    if hi - lo <= INSERTION_SORT_THRESHOLD {
+        steps.push_front(SortType::Line(1));
+        sort_subrange(arr, steps, lo, hi, insertion_sort, cmp);
+        return;
+    }
+    // Quick sort went too deep, heap sort keeps the range O(n log n)
+    if depth_limit == 0 {
+        steps.push_front(SortType::Line(2));
+        sort_subrange(arr, steps, lo, hi, heap_sort, cmp);
+        return;
+    }
+
+    steps.push_front(SortType::Line(3));
+    let pivot_index = choose_pivot(arr, steps, cmp, lo, hi, FixedPivot::MedianOfThree);
+    steps.push_front(SortType::Line(4));
+    let pivot_index = without_lines(steps, |steps| partition_lomuto(arr, steps, cmp, lo, hi, pivot_index));
+    _intro_sort(arr, steps, cmp, lo, pivot_index, depth_limit - 1);
+    _intro_sort(arr, steps, cmp, pivot_index + 1, hi, depth_limit - 1);
+}
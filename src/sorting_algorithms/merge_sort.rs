@@ -2,61 +2,87 @@ use std::collections::VecDeque;
 
 use crate::services::sorter::SortType;
 
-pub fn merge_sort<T: Copy + Clone + Ord>(items: &mut Vec<T>, steps: &mut VecDeque<SortType<T>>) {
-    _merge_sort(items, steps, 0);
+use super::Comparator;
+
+pub const PSEUDOCODE: &[&str] = &[
+    "merge_sort(a):",
+    "    if len(a) < 2: return",
+    "    left = merge_sort(a[..n / 2]), right = merge_sort(a[n / 2..])",
+    "    a = merge(left, right)",
+    "merge(a, b):",
+    "    while both have items",
+    "        if a[i] <= b[j]: append a[i]",
+    "        else: append b[j]",
+    "    append the rest of a, then the rest of b",
+];
+
+pub fn merge_sort<T: Copy>(items: &mut [T], steps: &mut VecDeque<SortType<T>>, cmp: &Comparator<T>) {
+    _merge_sort(items, steps, 0, cmp);
+    // Merged halves can still move in a later merge, so nothing is final before the end
+    if !items.is_empty() {
+        steps.push_front(SortType::MarkSorted(0, items.len()));
+    }
 }
 
-fn _merge_sort<T: Copy + Clone + PartialOrd>(
-    items: &mut Vec<T>,
-    mut steps: &mut VecDeque<SortType<T>>,
+fn _merge_sort<T: Copy>(
+    items: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
     start_i: usize,
+    cmp: &Comparator<T>,
 ) {
     if items.len() > 1 {
         let mid = items.len() / 2;
         let mut left_half = items[0..mid].to_vec();
         let mut right_half = items[mid..].to_vec();
-        _merge_sort(&mut left_half, &mut steps, start_i);
-        _merge_sort(&mut right_half, &mut steps, start_i + mid);
-        *items = merge(left_half, right_half, &mut steps, start_i);
+        steps.push_front(SortType::Line(2));
+        steps.push_front(SortType::Alloc(items.len()));
+        _merge_sort(&mut left_half, steps, start_i, cmp);
+        _merge_sort(&mut right_half, steps, start_i + mid, cmp);
+        items.copy_from_slice(&merge(left_half, right_half, steps, start_i, cmp));
+        // both halves and the merged buffer are dropped here
+        steps.push_front(SortType::Line(3));
+        steps.push_front(SortType::Free(items.len() * 2));
     }
 }
 
-fn merge<T: Copy + Clone + PartialOrd>(
+fn merge<T: Copy>(
     a: Vec<T>,
     b: Vec<T>,
     steps: &mut VecDeque<SortType<T>>,
     start_i: usize,
+    cmp: &Comparator<T>,
 ) -> Vec<T> {
     let size = a.len() + b.len();
     let mut merged: Vec<T> = Vec::with_capacity(size);
+    steps.push_front(SortType::Line(3));
+    steps.push_front(SortType::FocusRange(start_i, start_i + size));
+    steps.push_front(SortType::Alloc(size));
 
     let mut i = 0; // Idx for a
     let mut j = 0; // Idx for b
 
-    // Loop through a and b, adding the smallest values between them to `merged`
+    // Loop through a and b, adding the smallest values between them to `merged`.
+    // Both halves stay on screen until the merged buffer is copied back over them.
     while i < a.len() && j < b.len() {
-        if a[i] < b[j] {
+        let is_left = cmp(&a[i], &b[j]).is_le();
+        steps.push_front(SortType::Line(if is_left { 6 } else { 7 }));
+        steps.push_front(SortType::Compare(start_i + i, start_i + a.len() + j));
+        if is_left {
             merged.push(a[i]);
-            steps.push_front(SortType::Set(start_i + merged.len() - 1, a[i]));
             i += 1;
         } else {
             merged.push(b[j]);
-            steps.push_front(SortType::Set(start_i + merged.len() - 1, b[j]));
             j += 1;
         }
     }
 
     // Add all remaining values
-    while i < a.len() {
-        merged.push(a[i]);
-        steps.push_front(SortType::Set(start_i + merged.len() - 1, a[i]));
-        i += 1;
-    }
-    while j < b.len() {
-        merged.push(b[j]);
-        steps.push_front(SortType::Set(start_i + merged.len() - 1, b[j]));
-        j += 1;
-    }
+    merged.extend_from_slice(&a[i..]);
+    merged.extend_from_slice(&b[j..]);
 
+    steps.push_front(SortType::Line(3));
+    for (offset, &item) in merged.iter().enumerate() {
+        steps.push_front(SortType::Set(start_i + offset, item));
+    }
     merged
 }
@@ -1,6 +1,294 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use crate::services::sorter::SortType;
+
 pub mod merge_sort;
 pub mod bubble_sort;
 pub mod heap_sort;
 pub mod quick_sort;
 pub mod insertion_sort;
 pub mod shell_sort;
+pub mod bucket_sort;
+pub mod radix_sort;
+pub mod counting_sort;
+pub mod pigeonhole_sort;
+pub mod intro_sort;
+pub mod pdq_sort;
+pub mod tim_sort;
+
+/// Orders two items, every comparison sort goes through one instead of `<` and `>`
+pub type Comparator<'a, T> = dyn Fn(&T, &T) -> Ordering + 'a;
+/// Integer standing for an item, the counting and radix sorts order items by it
+pub type KeyFn<'a, T> = dyn Fn(&T) -> i64 + 'a;
+
+/// Most keys the counting sorts allocate a slot for, wider inputs are merge sorted by key instead
+pub const MAX_KEY_SPAN: u64 = 10_000;
+
+pub type SortStepsFn<T> = fn(&mut [T], &mut VecDeque<SortType<T>>, &Comparator<T>);
+
+/// Runs `sort` on arr[lo..hi] and records its steps with indexes of the whole array.
+pub fn sort_subrange<T>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    lo: usize,
+    hi: usize,
+    sort: SortStepsFn<T>,
+    cmp: &Comparator<T>,
+) {
+    without_lines(steps, |steps| {
+        let mut subrange_steps = VecDeque::new();
+        sort(&mut arr[lo..hi], &mut subrange_steps, cmp);
+        while let Some(step) = subrange_steps.pop_back() {
+            steps.push_front(step.shifted(lo));
+        }
+    })
+}
+
+/// Number of keys from the smallest to the largest one in arr
+pub fn key_span<T>(arr: &[T], key: &KeyFn<T>) -> u64 {
+    let min = arr.iter().map(key).min().unwrap_or(0);
+    let max = arr.iter().map(key).max().unwrap_or(0);
+    max.abs_diff(min).saturating_add(1)
+}
+
+/// Runs a helper whose pseudocode lines belong to another algorithm and keeps only its steps.
+/// The caller marks the line the whole call stands for.
+pub fn without_lines<T, R>(
+    steps: &mut VecDeque<SortType<T>>,
+    run: impl FnOnce(&mut VecDeque<SortType<T>>) -> R,
+) -> R {
+    let mut helper_steps = VecDeque::new();
+    let result = run(&mut helper_steps);
+    while let Some(step) = helper_steps.pop_back() {
+        if !matches!(step, SortType::Line(_)) {
+            steps.push_front(step);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use rand::Rng;
+
+    use crate::helpers::{get_new_generation, rng_from_seed, Distribution};
+
+    use super::bubble_sort::bubble_sort;
+    use super::bucket_sort::bucket_sort;
+    use super::counting_sort::counting_sort;
+    use super::heap_sort::heap_sort;
+    use super::insertion_sort::insertion_sort;
+    use super::intro_sort::intro_sort;
+    use super::merge_sort::merge_sort;
+    use super::pdq_sort::pdq_sort;
+    use super::pigeonhole_sort::pigeonhole_sort;
+    use super::quick_sort::{quick_sort, PartitionScheme, PivotChoice};
+    use super::radix_sort::{radix_sort_lsd, radix_sort_msd};
+    use super::shell_sort::shell_sort;
+    use super::tim_sort::tim_sort;
+    use super::*;
+
+    type Sort = Box<dyn Fn(&mut [i32], &mut VecDeque<SortType<i32>>, &Comparator<i32>)>;
+    type KeySortFn = fn(&mut [i32], &mut VecDeque<SortType<i32>>, &KeyFn<i32>);
+
+    // Every algorithm and variant, the integer key sorts follow the comparator's direction
+    fn all_sorts() -> Vec<(String, Sort)> {
+        let mut sorts: Vec<(String, Sort)> = Vec::new();
+        let by_cmp: [(&str, SortStepsFn<i32>); 8] = [
+            ("bubble", bubble_sort),
+            ("insertion", insertion_sort),
+            ("shell", shell_sort),
+            ("heap", heap_sort),
+            ("merge", merge_sort),
+            ("intro", intro_sort),
+            ("pdq", pdq_sort),
+            ("tim", tim_sort),
+        ];
+        for (name, sort) in by_cmp {
+            sorts.push((name.to_string(), Box::new(sort)));
+        }
+        let by_key: [(&str, KeySortFn); 3] =
+            [("bucket", bucket_sort), ("counting", counting_sort), ("pigeonhole", pigeonhole_sort)];
+        for (name, sort) in by_key {
+            sorts.push((name.to_string(), Box::new(move |arr, steps, cmp| sort(arr, steps, &|&item| key_of(cmp, item)))));
+        }
+        for base in [2, 4, 10, 16] {
+            sorts.push((
+                format!("radix lsd base {}", base),
+                Box::new(move |arr, steps, cmp| radix_sort_lsd(arr, steps, &|&item| key_of(cmp, item), base)),
+            ));
+            sorts.push((
+                format!("radix msd base {}", base),
+                Box::new(move |arr, steps, cmp| radix_sort_msd(arr, steps, &|&item| key_of(cmp, item), base)),
+            ));
+        }
+        for scheme in [PartitionScheme::Lomuto, PartitionScheme::Hoare, PartitionScheme::ThreeWay] {
+            for pivot in [
+                PivotChoice::First,
+                PivotChoice::Last,
+                PivotChoice::Middle,
+                PivotChoice::MedianOfThree,
+                PivotChoice::Random,
+            ] {
+                sorts.push((
+                    format!("quick {:?} {:?}", scheme, pivot),
+                    Box::new(move |arr, steps, cmp| quick_sort(arr, steps, cmp, scheme, pivot, 11)),
+                ));
+            }
+        }
+        sorts
+    }
+
+    // Integer key in the comparator's direction, only ascending and descending integers are used here
+    fn key_of(cmp: &Comparator<i32>, item: i32) -> i64 {
+        if cmp(&0, &1).is_lt() {
+            item as i64
+        } else {
+            -(item as i64)
+        }
+    }
+
+    fn inputs() -> Vec<Vec<i32>> {
+        let mut inputs = vec![
+            vec![],
+            vec![7],
+            vec![1, 2],
+            vec![2, 1],
+            vec![4, 4],
+            vec![5; 33],
+            (1..=64).collect(),
+            (-40..40).collect(),
+            vec![-2_000_000_000, 2_000_000_000, 5],
+        ];
+        let distributions = [
+            Distribution::Shuffled,
+            Distribution::Sorted,
+            Distribution::Reversed,
+            Distribution::NearlySorted,
+            Distribution::FewUnique,
+            Distribution::Sawtooth,
+            Distribution::OrganPipe,
+            Distribution::Gaussian,
+            Distribution::PartitionedHalves,
+        ];
+        for (idx, distribution) in distributions.iter().enumerate() {
+            for items_count in [3, 10, 33, 100, 200] {
+                inputs.push(get_new_generation(&items_count, distribution, idx as u64));
+            }
+        }
+        // Patterns that catch adaptive sorts out: runs, pipes, few values and long equal stretches
+        let len = 300;
+        inputs.extend([
+            (0..len).collect(),
+            (0..len).rev().collect(),
+            (0..len).map(|x| x % 7).collect(),
+            (0..len).map(|x| if x % 2 == 0 { x } else { len - x }).collect(),
+            (0..len).map(|x| (x * 7919) % 311).collect(),
+            vec![5; len as usize],
+        ]);
+        // Negative and repeated values, at sizes no distribution above has
+        let mut rng = rng_from_seed(22);
+        for items_count in [5, 17, 64, 129] {
+            for _ in 0..3 {
+                inputs.push((0..items_count).map(|_| rng.gen_range(-300..300)).collect());
+            }
+        }
+        inputs
+    }
+
+    // Plays the writes and swaps back over the initial data, checks every index on the way
+    // and that each buffer allocated is freed again
+    fn replay(initial: &[i32], steps: &VecDeque<SortType<i32>>) -> Result<Vec<i32>, String> {
+        let mut items = initial.to_vec();
+        let len = items.len();
+        let mut aux_memory = 0;
+        for step in steps.iter().rev() {
+            match *step {
+                SortType::Set(idx, value) if idx < len => items[idx] = value,
+                SortType::Swap(idx1, idx2) if idx1 < len && idx2 < len => items.swap(idx1, idx2),
+                SortType::Compare(idx1, idx2) if idx1 < len && idx2 < len => (),
+                SortType::Bucket(idx, _) if idx < len => (),
+                SortType::Run(lo, hi) | SortType::MarkSorted(lo, hi) | SortType::FocusRange(lo, hi) if lo < hi && hi <= len => (),
+                SortType::MarkPivot(idx) if idx < len => (),
+                SortType::HeapBoundary(end) if end <= len => (),
+                SortType::Set(..)
+                | SortType::Swap(..)
+                | SortType::Compare(..)
+                | SortType::Bucket(..)
+                | SortType::Run(..)
+                | SortType::MarkSorted(..)
+                | SortType::FocusRange(..)
+                | SortType::MarkPivot(_)
+                | SortType::HeapBoundary(_) => {
+                    return Err(format!("{} is out of {} items", step, len));
+                }
+                SortType::Alloc(size) => aux_memory += size,
+                SortType::Free(size) => {
+                    aux_memory = aux_memory.checked_sub(size).ok_or_else(|| format!("{} frees more than allocated", step))?;
+                }
+                _ => (),
+            }
+        }
+        if aux_memory != 0 {
+            return Err(format!("{} items of aux memory are never freed", aux_memory));
+        }
+        Ok(items)
+    }
+
+    #[test]
+    fn test_every_step_stream_replays_to_the_sorted_input() {
+        let ascending = |a: &i32, b: &i32| a.cmp(b);
+        let descending = |a: &i32, b: &i32| b.cmp(a);
+        let orders: [(&str, &Comparator<i32>); 2] = [("ascending", &ascending), ("descending", &descending)];
+        for initial in inputs() {
+            for (order, cmp) in orders {
+                let mut expected = initial.clone();
+                expected.sort_by(cmp);
+                for (name, sort) in all_sorts() {
+                    let mut items = initial.clone();
+                    let mut steps = VecDeque::new();
+                    sort(&mut items, &mut steps, cmp);
+                    assert_eq!(items, expected, "{} {} on {:?}", name, order, initial);
+                    assert_eq!(replay(&initial, &steps), Ok(items), "{} {} on {:?}", name, order, initial);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_compare_steps_show_the_compared_items() {
+        // The key sorts only call the comparator to build keys
+        let shows_both = |name: &str| {
+            !["bucket", "counting", "pigeonhole", "radix"].iter().any(|prefix| name.starts_with(prefix))
+        };
+        for (name, sort) in all_sorts().into_iter().filter(|(name, _)| shows_both(name)) {
+            for initial in inputs() {
+                let compared = RefCell::new(Vec::new());
+                let cmp = |a: &i32, b: &i32| {
+                    compared.borrow_mut().push((*a.min(b), *a.max(b)));
+                    a.cmp(b)
+                };
+                let mut steps = VecDeque::new();
+                sort(&mut initial.clone(), &mut steps, &cmp);
+
+                let mut items = initial.clone();
+                let mut shown = Vec::new();
+                for step in steps.iter().rev() {
+                    match *step {
+                        SortType::Set(idx, value) => items[idx] = value,
+                        SortType::Swap(idx1, idx2) => items.swap(idx1, idx2),
+                        SortType::Compare(idx1, idx2) => {
+                            shown.push((items[idx1].min(items[idx2]), items[idx1].max(items[idx2])))
+                        }
+                        _ => (),
+                    }
+                }
+                assert_eq!(shown, *compared.borrow(), "{} on {:?}", name, initial);
+            }
+        }
+    }
+
+}
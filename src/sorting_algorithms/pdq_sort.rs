@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+
+use crate::services::sorter::SortType;
+
+use super::heap_sort::heap_sort;
+use super::insertion_sort::insertion_sort;
+use super::quick_sort::{choose_pivot, FixedPivot};
+use super::{sort_subrange, Comparator};
+
+const INSERTION_SORT_THRESHOLD: usize = 24;
+const PARTIAL_INSERTION_SORT_LIMIT: usize = 8;
+
+pub const PSEUDOCODE: &[&str] = &[
+    "pdq_sort(a, lo, hi, bad_allowed):",
+    "    if hi - lo <= 24: insertion sort a[lo..hi], return",
+    "    if bad_allowed == 0: heap sort a[lo..hi], return",
+    "    move the median of three to a[lo]",
+    "    if the predecessor equals a[lo]: set aside every item equal to it, continue after them",
+    "    p = partition a[lo..hi] around a[lo]",
+    "    if a side is shorter than n / 8: bad_allowed -= 1, break patterns on both sides",
+    "    else if nothing moved: partial insertion sort both sides, return if that sorted them",
+    "    pdq_sort(a, lo, p), then continue with a[p + 1..hi]",
+];
+
+// A simplified pattern-defeating quicksort, the idea behind slice::sort_unstable
+pub fn pdq_sort<T: Copy>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, cmp: &Comparator<T>) {
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+    let bad_allowed = len.ilog2();
+    _pdq_sort(arr, steps, cmp, 0, len, bad_allowed, None);
+}
+
+fn _pdq_sort<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    mut lo: usize,
+    hi: usize,
+    mut bad_allowed: u32,
+    mut predecessor: Option<usize>,
+) {
+    loop {
+        let len = hi - lo;
+        if len <= INSERTION_SORT_THRESHOLD {
+            steps.push_front(SortType::Line(1));
+            sort_subrange(arr, steps, lo, hi, insertion_sort, cmp);
+            return;
+        }
+        // Too many bad pivots in a row, heap sort guarantees O(n log n)
+        if bad_allowed == 0 {
+            steps.push_front(SortType::Line(2));
+            sort_subrange(arr, steps, lo, hi, heap_sort, cmp);
+            return;
+        }
+
+        steps.push_front(SortType::Line(3));
+        let pivot_index = choose_pivot(arr, steps, cmp, lo, hi, FixedPivot::MedianOfThree);
+        swap(arr, steps, lo, pivot_index);
+
+        // The predecessor is not greater than anything in the range, so when it equals the
+        // pivot every item equal to the pivot can be put aside at once
+        if let Some(predecessor) = predecessor {
+            steps.push_front(SortType::Line(4));
+            steps.push_front(SortType::Compare(predecessor, lo));
+            if cmp(&arr[predecessor], &arr[lo]).is_ge() {
+                let (pivot_index, _) = partition(arr, steps, cmp, lo, hi, true);
+                lo = pivot_index + 1;
+                continue;
+            }
+        }
+
+        steps.push_front(SortType::Line(5));
+        let (pivot_index, was_partitioned) = partition(arr, steps, cmp, lo, hi, false);
+        let left_len = pivot_index - lo;
+        let right_len = hi - pivot_index - 1;
+
+        if left_len < len / 8 || right_len < len / 8 {
+            bad_allowed -= 1;
+            steps.push_front(SortType::Line(6));
+            break_patterns(arr, steps, lo, pivot_index);
+            break_patterns(arr, steps, pivot_index + 1, hi);
+        } else if was_partitioned {
+            steps.push_front(SortType::Line(7));
+            if partial_insertion_sort(arr, steps, cmp, lo, pivot_index)
+                && partial_insertion_sort(arr, steps, cmp, pivot_index + 1, hi)
+            {
+                // The input looked sorted and both halves were fixed with a few moves
+                return;
+            }
+        }
+
+        steps.push_front(SortType::Line(8));
+        _pdq_sort(arr, steps, cmp, lo, pivot_index, bad_allowed, predecessor);
+        predecessor = Some(pivot_index);
+        lo = pivot_index + 1;
+    }
+}
+
+fn swap<T>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, a: usize, b: usize) {
+    if a != b {
+        arr.swap(a, b);
+        steps.push_front(SortType::Swap(a, b));
+    }
+}
+
+// Partitions arr[lo..hi] around the pivot at lo and returns its final position. Items equal
+// to the pivot go to the left when `equal_left` is set, otherwise to the right. The flag in the
+// result tells whether no item had to be moved.
+fn partition<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    lo: usize,
+    hi: usize,
+    equal_left: bool,
+) -> (usize, bool) {
+    let pivot = arr[lo];
+    let goes_left = |steps: &mut VecDeque<SortType<T>>, idx: usize, item: T| {
+        steps.push_front(SortType::Compare(idx, lo));
+        let ordering = cmp(&item, &pivot);
+        if equal_left { ordering.is_le() } else { ordering.is_lt() }
+    };
+
+    let mut first = lo + 1;
+    while first < hi && goes_left(steps, first, arr[first]) {
+        first += 1;
+    }
+    let mut last = hi;
+    while last > first && !goes_left(steps, last - 1, arr[last - 1]) {
+        last -= 1;
+    }
+    let was_partitioned = first >= last;
+
+    while first < last {
+        swap(arr, steps, first, last - 1);
+        first += 1;
+        last -= 1;
+        while first < last && goes_left(steps, first, arr[first]) {
+            first += 1;
+        }
+        while last > first && !goes_left(steps, last - 1, arr[last - 1]) {
+            last -= 1;
+        }
+    }
+
+    let pivot_index = first - 1;
+    swap(arr, steps, lo, pivot_index);
+    (pivot_index, was_partitioned)
+}
+
+// Insertion sort that gives up after a few moved items, returns whether the range got sorted
+fn partial_insertion_sort<T>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    lo: usize,
+    hi: usize,
+) -> bool {
+    let mut moved = 0;
+    for i in lo + 1..hi {
+        steps.push_front(SortType::Compare(i - 1, i));
+        if cmp(&arr[i - 1], &arr[i]).is_le() {
+            continue;
+        }
+        moved += 1;
+        if moved > PARTIAL_INSERTION_SORT_LIMIT {
+            return false;
+        }
+
+        let mut j = i;
+        while j > lo {
+            steps.push_front(SortType::Compare(j - 1, j));
+            if cmp(&arr[j - 1], &arr[j]).is_le() {
+                break;
+            }
+            swap(arr, steps, j - 1, j);
+            j -= 1;
+        }
+    }
+    true
+}
+
+// Scatters a few items around so patterns that made the pivot bad do not repeat
+fn break_patterns<T>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, lo: usize, hi: usize) {
+    let len = hi - lo;
+    if len < INSERTION_SORT_THRESHOLD {
+        return;
+    }
+    let quarter = len / 4;
+    swap(arr, steps, lo, lo + quarter);
+    swap(arr, steps, hi - 1, hi - 1 - quarter);
+    let mid = lo + len / 2;
+    swap(arr, steps, mid, mid - quarter / 2);
+}
@@ -0,0 +1,87 @@
+use std::collections::VecDeque;
+
+use crate::services::sorter::SortType;
+
+use super::merge_sort::merge_sort;
+use super::{key_span, without_lines, KeyFn, MAX_KEY_SPAN};
+
+pub const PSEUDOCODE: &[&str] = &[
+    "holes = one empty list per value in min..=max",
+    "for each item x",
+    "    append x to holes[x - min]",
+    "i = 0",
+    "for each hole h in order",
+    "    while h is not empty: a[i] = take first of h, i += 1",
+    "if max - min is too large: merge sort a by key instead",
+];
+
+pub fn pigeonhole_sort<T: Copy>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, key: &KeyFn<T>) {
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+
+    if key_span(arr, key) > MAX_KEY_SPAN {
+        // A slot per key would not fit in memory
+        let cmp = |a: &T, b: &T| key(a).cmp(&key(b));
+        steps.push_front(SortType::Line(6));
+        without_lines(steps, |steps| merge_sort(arr, steps, &cmp));
+        return;
+    }
+
+    let min = arr.iter().map(key).min().unwrap();
+    let max = arr.iter().map(key).max().unwrap();
+    let range = (max - min + 1) as usize;
+
+    // Drop every item into the hole of its own value
+    let mut holes: Vec<VecDeque<T>> = vec![VecDeque::new(); range];
+    steps.push_front(SortType::Line(0));
+    steps.push_front(SortType::Alloc(range + len));
+    for (idx, &item) in arr.iter().enumerate() {
+        let hole = (key(&item) - min) as usize;
+        holes[hole].push_back(item);
+        steps.push_front(SortType::Line(2));
+        steps.push_front(SortType::Bucket(idx, hole));
+        steps.push_front(SortType::AuxSet(hole, holes[hole].len()));
+    }
+
+    // Empty the holes back into the array in order
+    let mut idx = 0;
+    for (hole, items) in holes.iter_mut().enumerate() {
+        while let Some(item) = items.pop_front() {
+            arr[idx] = item;
+            steps.push_front(SortType::Line(5));
+            steps.push_front(SortType::Set(idx, item));
+            steps.push_front(SortType::AuxSet(hole, items.len()));
+            idx += 1;
+        }
+    }
+    steps.push_front(SortType::Free(range + len));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::stability::Tagged;
+
+    use super::*;
+
+    #[test]
+    fn test_pigeonhole_sort_is_stable_and_empties_its_holes() {
+        let mut items = Tagged::tag_all(&[2, 0, 2, 1, 0]);
+        let mut steps = VecDeque::new();
+        pigeonhole_sort(&mut items, &mut steps, &|item| item.key as i64);
+        let sorted: Vec<(i32, usize)> = items.iter().map(|item| (item.key, item.tag)).collect();
+        assert_eq!(sorted, vec![(0, 1), (0, 4), (1, 3), (2, 0), (2, 2)]);
+
+        let mut holes = [0; 3];
+        let mut fullest = holes;
+        for step in steps.iter().rev() {
+            if let SortType::AuxSet(hole, count) = *step {
+                holes[hole] = count;
+                fullest[hole] = fullest[hole].max(count);
+            }
+        }
+        assert_eq!(fullest, [2, 1, 2]);
+        assert_eq!(holes, [0; 3]);
+    }
+}
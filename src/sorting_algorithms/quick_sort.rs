@@ -1,47 +1,339 @@
 use std::collections::VecDeque;
 
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::helpers::rng_from_seed;
 use crate::services::sorter::SortType;
 
-pub fn quick_sort<T: Copy + Clone + Ord>(
-    arr: &mut Vec<T>, 
-    steps: &mut VecDeque<SortType<T>>, 
+use super::Comparator;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PartitionScheme {
+    Lomuto,
+    Hoare,
+    ThreeWay,
+}
+
+impl PartitionScheme {
+    pub fn from_string(s: &str) -> Result<PartitionScheme, &'static str> {
+        match s {
+            "lomuto" => Ok(PartitionScheme::Lomuto),
+            "hoare" => Ok(PartitionScheme::Hoare),
+            "three_way" => Ok(PartitionScheme::ThreeWay),
+            _ => Err("Invalid variant"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PivotChoice {
+    First,
+    Last,
+    Middle,
+    MedianOfThree,
+    Random,
+}
+
+impl PivotChoice {
+    pub fn from_string(s: &str) -> Result<PivotChoice, &'static str> {
+        match s {
+            "first" => Ok(PivotChoice::First),
+            "last" => Ok(PivotChoice::Last),
+            "middle" => Ok(PivotChoice::Middle),
+            "median_of_three" => Ok(PivotChoice::MedianOfThree),
+            "random" => Ok(PivotChoice::Random),
+            _ => Err("Invalid variant"),
+        }
+    }
+
+    /// The same choice as a rule that only looks at the range, random pivots have none
+    pub fn fixed(self) -> Option<FixedPivot> {
+        match self {
+            PivotChoice::First => Some(FixedPivot::First),
+            PivotChoice::Last => Some(FixedPivot::Last),
+            PivotChoice::Middle => Some(FixedPivot::Middle),
+            PivotChoice::MedianOfThree => Some(FixedPivot::MedianOfThree),
+            PivotChoice::Random => None,
+        }
+    }
+}
+
+/// Pivot choices that need nothing but the range, random pivots come from quick sort's generator
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FixedPivot {
+    First,
+    Last,
+    Middle,
+    MedianOfThree,
+}
+
+const SHARED_PSEUDOCODE: [&str; 7] = [
+    "quick_sort(a, lo, hi):",
+    "    if hi - lo < 2: return",
+    "    p = choose_pivot(a, lo, hi)",
+    "    (left_end, right_start) = partition(a, lo, hi, p)",
+    "    quick_sort(a, lo, left_end)",
+    "    quick_sort(a, right_start, hi)",
+    "",
+];
+
+const LOMUTO_PSEUDOCODE: [&str; 8] = [
+    "partition(a, lo, hi, p):",
+    "    swap a[p] and a[hi - 1]",
+    "    i = lo",
+    "    for j in lo..hi - 1",
+    "        if a[j] <= a[hi - 1]",
+    "            swap a[i] and a[j], i += 1",
+    "    swap a[i] and a[hi - 1]",
+    "    return (i, i + 1)",
+];
+
+const HOARE_PSEUDOCODE: [&str; 8] = [
+    "partition(a, lo, hi, p):",
+    "    swap a[p] and a[lo], pivot = a[lo]",
+    "    i = lo, j = hi - 1",
+    "    loop",
+    "        while a[i] < pivot: i += 1",
+    "        while a[j] > pivot: j -= 1",
+    "        if i >= j: return (j + 1, j + 1)",
+    "        swap a[i] and a[j], i += 1, j -= 1",
+];
+
+const THREE_WAY_PSEUDOCODE: [&str; 7] = [
+    "partition(a, lo, hi, p):",
+    "    pivot = a[p], lt = lo, i = lo, gt = hi",
+    "    while i < gt",
+    "        if a[i] < pivot: swap a[lt] and a[i], lt += 1, i += 1",
+    "        else if a[i] > pivot: gt -= 1, swap a[i] and a[gt]",
+    "        else: i += 1",
+    "    return (lt, gt)",
+];
+
+// Partition lines are numbered after the shared ones
+const PARTITION_LINE: usize = SHARED_PSEUDOCODE.len();
+
+pub fn pseudocode(scheme: PartitionScheme) -> Vec<&'static str> {
+    let partition: &[&str] = match scheme {
+        PartitionScheme::Lomuto => &LOMUTO_PSEUDOCODE,
+        PartitionScheme::Hoare => &HOARE_PSEUDOCODE,
+        PartitionScheme::ThreeWay => &THREE_WAY_PSEUDOCODE,
+    };
+    SHARED_PSEUDOCODE.iter().chain(partition).copied().collect()
+}
+
+pub fn quick_sort<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    scheme: PartitionScheme,
+    pivot_choice: PivotChoice,
+    seed: u64,
 ) {
-    _quick_sort(arr, steps, 0)
+    let mut rng = rng_from_seed(seed);
+    _quick_sort(arr, steps, cmp, 0, arr.len(), scheme, pivot_choice, &mut rng)
 }
 
-pub fn _quick_sort<T: Copy + Clone + Ord>(
-    arr: &mut Vec<T>, 
-    steps: &mut VecDeque<SortType<T>>, 
-    divide_index: usize
+// Sorts arr[lo..hi] in place
+#[allow(clippy::too_many_arguments)]
+fn _quick_sort<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    lo: usize,
+    hi: usize,
+    scheme: PartitionScheme,
+    pivot_choice: PivotChoice,
+    rng: &mut StdRng,
 ) {
-    let len = arr.len();
-    if len < 2 {
-        return; // Already sorted if the array has 0 or 1 element
+    if hi - lo < 2 {
+        // Already sorted if the range has 0 or 1 element
+        if hi > lo {
+            steps.push_front(SortType::MarkSorted(lo, hi));
+        }
+        return;
     }
 
-    let pivot_index = partition(arr, steps, divide_index);
-    _quick_sort(&mut arr[0..pivot_index].to_vec(), steps, divide_index);
-    _quick_sort(&mut arr[pivot_index + 1..].to_vec(), steps, divide_index + pivot_index + 1);
+    steps.push_front(SortType::FocusRange(lo, hi));
+    steps.push_front(SortType::Line(2));
+    let pivot_index = match pivot_choice.fixed() {
+        Some(fixed) => choose_pivot(arr, steps, cmp, lo, hi, fixed),
+        None => rng.gen_range(lo..hi),
+    };
+    steps.push_front(SortType::MarkPivot(pivot_index));
+    let (left_end, right_start) = match scheme {
+        PartitionScheme::Lomuto => {
+            let pivot_index = partition_lomuto(arr, steps, cmp, lo, hi, pivot_index);
+            (pivot_index, pivot_index + 1)
+        }
+        PartitionScheme::Hoare => {
+            let split = partition_hoare(arr, steps, cmp, lo, hi, pivot_index);
+            (split + 1, split + 1)
+        }
+        PartitionScheme::ThreeWay => partition_three_way(arr, steps, cmp, lo, hi, pivot_index),
+    };
+    // Lomuto leaves the pivot and three-way every item equal to it in place, Hoare only splits
+    if left_end < right_start {
+        steps.push_front(SortType::MarkSorted(left_end, right_start));
+    }
+    _quick_sort(arr, steps, cmp, lo, left_end, scheme, pivot_choice, rng);
+    _quick_sort(arr, steps, cmp, right_start, hi, scheme, pivot_choice, rng);
 }
 
-fn partition<T: Ord>(arr: &mut Vec<T>, steps: &mut VecDeque<SortType<T>>, divide_index: usize) -> usize {
-    let len = arr.len();
-    let pivot_index = len / 2;
+/// Index of the pivot for arr[lo..hi]
+pub fn choose_pivot<T>(
+    arr: &[T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    lo: usize,
+    hi: usize,
+    pivot: FixedPivot,
+) -> usize {
+    let last = hi - 1;
+    let mid = lo + (hi - lo) / 2;
+    match pivot {
+        FixedPivot::First => lo,
+        FixedPivot::Last => last,
+        FixedPivot::Middle => mid,
+        FixedPivot::MedianOfThree => {
+            let mut less = |a: usize, b: usize| {
+                steps.push_front(SortType::Compare(a, b));
+                cmp(&arr[a], &arr[b]).is_lt()
+            };
+            if less(lo, mid) {
+                if less(mid, last) {
+                    mid
+                } else if less(lo, last) {
+                    last
+                } else {
+                    lo
+                }
+            } else if less(lo, last) {
+                lo
+            } else if less(mid, last) {
+                last
+            } else {
+                mid
+            }
+        }
+    }
+}
 
-    arr.swap(pivot_index, len - 1);
-    steps.push_front(SortType::Swap(divide_index + pivot_index, divide_index + len - 1));
+// Swaps two items and keeps track of where the pivot value went
+fn swap_tracking<T>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    a: usize,
+    b: usize,
+    pivot_index: &mut usize,
+) {
+    if a == b {
+        return;
+    }
+    arr.swap(a, b);
+    steps.push_front(SortType::Swap(a, b));
+    if *pivot_index == a {
+        *pivot_index = b;
+    } else if *pivot_index == b {
+        *pivot_index = a;
+    }
+}
+
+pub fn partition_lomuto<T>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    lo: usize,
+    hi: usize,
+    mut pivot_index: usize,
+) -> usize {
+    let last = hi - 1;
+    steps.push_front(SortType::Line(PARTITION_LINE + 1));
+    swap_tracking(arr, steps, pivot_index, last, &mut pivot_index);
 
-    let mut i = 0;
-    for j in 0..len - 1 {
-        if arr[j] <= arr[len - 1] {
-            arr.swap(i, j);
-            steps.push_front(SortType::Swap(divide_index + i, divide_index + j));
+    let mut i = lo;
+    for j in lo..last {
+        steps.push_front(SortType::Line(PARTITION_LINE + 4));
+        steps.push_front(SortType::Compare(j, last));
+        if cmp(&arr[j], &arr[last]).is_le() {
+            steps.push_front(SortType::Line(PARTITION_LINE + 5));
+            swap_tracking(arr, steps, i, j, &mut pivot_index);
             i += 1;
         }
     }
 
-    arr.swap(i, len - 1);
-    steps.push_front(SortType::Swap(divide_index + i, divide_index + len - 1));
+    steps.push_front(SortType::Line(PARTITION_LINE + 6));
+    swap_tracking(arr, steps, i, last, &mut pivot_index);
     i
 }
 
+// Returns the last index of the left part, both parts are non-empty
+fn partition_hoare<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    lo: usize,
+    hi: usize,
+    mut pivot_index: usize,
+) -> usize {
+    steps.push_front(SortType::Line(PARTITION_LINE + 1));
+    swap_tracking(arr, steps, pivot_index, lo, &mut pivot_index);
+    let pivot = arr[lo];
+
+    let mut i = lo;
+    let mut j = hi - 1;
+    loop {
+        steps.push_front(SortType::Line(PARTITION_LINE + 4));
+        steps.push_front(SortType::Compare(i, pivot_index));
+        while cmp(&arr[i], &pivot).is_lt() {
+            i += 1;
+            steps.push_front(SortType::Compare(i, pivot_index));
+        }
+        steps.push_front(SortType::Line(PARTITION_LINE + 5));
+        steps.push_front(SortType::Compare(j, pivot_index));
+        while cmp(&arr[j], &pivot).is_gt() {
+            j -= 1;
+            steps.push_front(SortType::Compare(j, pivot_index));
+        }
+        if i >= j {
+            return j;
+        }
+        steps.push_front(SortType::Line(PARTITION_LINE + 7));
+        swap_tracking(arr, steps, i, j, &mut pivot_index);
+        i += 1;
+        j -= 1;
+    }
+}
+
+// Dutch national flag partition, returns the bounds of the range equal to the pivot
+fn partition_three_way<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    lo: usize,
+    hi: usize,
+    mut pivot_index: usize,
+) -> (usize, usize) {
+    let pivot = arr[pivot_index];
+    let mut lt = lo;
+    let mut i = lo;
+    let mut gt = hi;
+
+    while i < gt {
+        steps.push_front(SortType::Line(PARTITION_LINE + 3));
+        steps.push_front(SortType::Compare(i, pivot_index));
+        let ordering = cmp(&arr[i], &pivot);
+        if ordering.is_lt() {
+            swap_tracking(arr, steps, lt, i, &mut pivot_index);
+            lt += 1;
+            i += 1;
+        } else if ordering.is_gt() {
+            gt -= 1;
+            steps.push_front(SortType::Line(PARTITION_LINE + 4));
+            swap_tracking(arr, steps, i, gt, &mut pivot_index);
+        } else {
+            i += 1;
+        }
+    }
+    (lt, gt)
+}
@@ -0,0 +1,170 @@
+use std::collections::VecDeque;
+
+use crate::services::sorter::SortType;
+
+use super::KeyFn;
+
+// Both variants share the counting pass, so its lines have the same numbers in each
+pub const LSD_PSEUDOCODE: &[&str] = &[
+    "for position in 0..digits(max)",
+    "    stable counting sort of a by digit(position)",
+    "        count the items per digit, prefix sums give where each digit starts",
+    "        write the items back digit by digit",
+];
+
+pub const MSD_PSEUDOCODE: &[&str] = &[
+    "msd(a, lo, hi, position):",
+    "    stable counting sort of a[lo..hi] by digit(position)",
+    "        count the items per digit, prefix sums give where each digit starts",
+    "        write the items back digit by digit",
+    "    if position > 0: msd(bucket, position - 1) for every digit bucket",
+];
+
+fn digit_of(key: u64, position: u32, base: u64) -> usize {
+    ((key / base.pow(position)) % base) as usize
+}
+
+// Keys are shifted by the minimum, so negative values sort the same way as positive ones.
+// Returns the minimum and the largest shifted key.
+fn keys_of<T>(arr: &[T], key: &KeyFn<T>) -> (i64, u64) {
+    let min = arr.iter().map(key).min().unwrap_or(0);
+    let max = arr.iter().map(key).max().unwrap_or(0);
+    (min, (max - min) as u64)
+}
+
+fn digits_count(max_key: u64, base: u64) -> u32 {
+    let mut count = 1;
+    let mut rest = max_key / base;
+    while rest > 0 {
+        count += 1;
+        rest /= base;
+    }
+    count
+}
+
+// Stable counting pass over arr[lo..hi] by a single digit, written back with Set steps.
+// Returns where each digit bucket starts, relative to lo.
+fn sort_by_digit<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    lo: usize,
+    hi: usize,
+    shifted: &dyn Fn(&T) -> u64,
+    position: u32,
+    base: u64,
+) -> Vec<usize> {
+    let mut counts = vec![0; base as usize];
+    steps.push_front(SortType::Line(2));
+    steps.push_front(SortType::Alloc(base as usize + hi - lo));
+    for &item in &arr[lo..hi] {
+        counts[digit_of(shifted(&item), position, base)] += 1;
+    }
+
+    let mut starts = vec![0; base as usize];
+    for digit in 1..base as usize {
+        starts[digit] = starts[digit - 1] + counts[digit - 1];
+    }
+
+    let mut next = starts.clone();
+    let mut output = arr[lo..hi].to_vec();
+    for &item in &arr[lo..hi] {
+        let digit = digit_of(shifted(&item), position, base);
+        output[next[digit]] = item;
+        next[digit] += 1;
+    }
+
+    steps.push_front(SortType::Line(3));
+    for (offset, item) in output.into_iter().enumerate() {
+        arr[lo + offset] = item;
+        steps.push_front(SortType::Set(lo + offset, item));
+    }
+    steps.push_front(SortType::Free(base as usize + hi - lo));
+    starts
+}
+
+pub fn radix_sort_lsd<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    key: &KeyFn<T>,
+    base: u32,
+) {
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+    let base = base.max(2) as u64;
+    let (min, max_key) = keys_of(arr, key);
+    let shifted = |item: &T| (key(item) - min) as u64;
+
+    for position in 0..digits_count(max_key, base) {
+        steps.push_front(SortType::Line(1));
+        steps.push_front(SortType::Digit(position));
+        sort_by_digit(arr, steps, 0, len, &shifted, position, base);
+    }
+}
+
+pub fn radix_sort_msd<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    key: &KeyFn<T>,
+    base: u32,
+) {
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+    let base = base.max(2) as u64;
+    let (min, max_key) = keys_of(arr, key);
+    let shifted = |item: &T| (key(item) - min) as u64;
+    let top_position = digits_count(max_key, base) - 1;
+
+    _radix_sort_msd(arr, steps, 0, len, &shifted, top_position, base);
+}
+
+fn _radix_sort_msd<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    lo: usize,
+    hi: usize,
+    shifted: &dyn Fn(&T) -> u64,
+    position: u32,
+    base: u64,
+) {
+    if hi - lo < 2 {
+        return;
+    }
+
+    steps.push_front(SortType::Line(1));
+    steps.push_front(SortType::Digit(position));
+    let starts = sort_by_digit(arr, steps, lo, hi, shifted, position, base);
+    if position == 0 {
+        return;
+    }
+
+    // Every bucket shares the current digit, so it is sorted further by the next one
+    for (digit, &start) in starts.iter().enumerate() {
+        let end = starts.get(digit + 1).copied().unwrap_or(hi - lo);
+        _radix_sort_msd(arr, steps, lo + start, lo + end, shifted, position - 1, base);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_radix_sorts_for_every_base() {
+        let initial = vec![170, -45, 75, 90, -802, 24, 2, 66, 66, 0];
+        let mut expected = initial.clone();
+        expected.sort();
+        for base in [2, 4, 10, 16] {
+            let mut items = initial.clone();
+            radix_sort_lsd(&mut items, &mut VecDeque::new(), &|&item| item as i64, base);
+            assert_eq!(items, expected);
+
+            let mut items = initial.clone();
+            radix_sort_msd(&mut items, &mut VecDeque::new(), &|&item| item as i64, base);
+            assert_eq!(items, expected);
+        }
+    }
+}
@@ -2,26 +2,45 @@ use std::collections::VecDeque;
 
 use crate::services::sorter::SortType;
 
-pub fn shell_sort<T: Copy + Clone + Ord>(arr: &mut Vec<T>, steps: &mut VecDeque<SortType<T>>) {
+use super::Comparator;
+
+pub const PSEUDOCODE: &[&str] = &[
+    "gap = n / 2",
+    "while gap > 0",
+    "    for i in gap..n",
+    "        j = i",
+    "        while j >= gap and a[j - gap] > a[j]",
+    "            swap a[j - gap] and a[j]",
+    "            j = j - gap",
+    "    gap = gap / 2",
+];
+
+pub fn shell_sort<T: Copy>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, cmp: &Comparator<T>) {
     let len = arr.len();
     let mut gap = len / 2;
 
     while gap > 0 {
         for i in gap..len {
+            // Items gap apart from i back to the start of the array are insertion sorted
+            steps.push_front(SortType::FocusRange(i % gap, i + 1));
+            // The item moves by swaps, so both compared items are always on screen
             let mut j = i;
-            let current_element = arr[i];
-
-            while j >= gap && arr[j - gap] > current_element {
-                arr[j] = arr[j - gap];
-                steps.push_front(SortType::Set(j, arr[j - gap]));
+            while j >= gap {
+                steps.push_front(SortType::Line(4));
+                steps.push_front(SortType::Compare(j - gap, j));
+                if cmp(&arr[j - gap], &arr[j]).is_le() {
+                    break;
+                }
+                arr.swap(j - gap, j);
+                steps.push_front(SortType::Line(5));
+                steps.push_front(SortType::Swap(j - gap, j));
                 j -= gap;
             }
-
-            arr[j] = current_element;
-            steps.push_front(SortType::Set(j, current_element));
         }
 
         gap /= 2;
     }
+    if len > 0 {
+        steps.push_front(SortType::MarkSorted(0, len));
+    }
 }
-
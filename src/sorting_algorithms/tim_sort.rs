@@ -0,0 +1,331 @@
+use std::collections::VecDeque;
+
+use crate::services::sorter::SortType;
+
+use super::Comparator;
+
+const MIN_MERGE: usize = 32;
+const MIN_GALLOP: usize = 7;
+
+pub const PSEUDOCODE: &[&str] = &[
+    "min_run = min_run_length(n)",
+    "for each run starting at lo",
+    "    find where the natural run ends, reversing it if strictly descending",
+    "    extend it to min_run items with binary insertion sort",
+    "    push the run, merge the top runs while their lengths break the invariants",
+    "merge_runs(lo, mid, hi):",
+    "    copy the left run aside",
+    "    while both runs have items: move the smaller front item to a[k]",
+    "        after min_gallop wins in a row, gallop and move a whole block",
+    "    move the rest of the left run",
+    "merge the remaining runs from the top of the stack",
+];
+
+pub fn tim_sort<T: Copy>(arr: &mut [T], steps: &mut VecDeque<SortType<T>>, cmp: &Comparator<T>) {
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+
+    let min_run = min_run_length(len);
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    let mut min_gallop = MIN_GALLOP;
+    let mut lo = 0;
+    while lo < len {
+        // Take the natural run and extend it up to min_run with binary insertion
+        steps.push_front(SortType::Line(2));
+        let mut hi = count_run_and_make_ascending(arr, steps, cmp, lo);
+        let forced_hi = (lo + min_run).min(len);
+        if hi < forced_hi {
+            steps.push_front(SortType::Line(3));
+            binary_insertion_sort(arr, steps, cmp, lo, hi, forced_hi);
+            hi = forced_hi;
+        }
+        steps.push_front(SortType::Line(4));
+        steps.push_front(SortType::Run(lo, hi));
+        runs.push((lo, hi));
+        merge_collapse(arr, steps, cmp, &mut runs, &mut min_gallop);
+        lo = hi;
+    }
+
+    while runs.len() > 1 {
+        steps.push_front(SortType::Line(10));
+        let idx = runs.len() - 2;
+        merge_at(arr, steps, cmp, &mut runs, idx, &mut min_gallop);
+    }
+}
+
+// Takes the 6 most significant bits of len and adds one if any of the rest is set,
+// so that len / min_run is a power of two or a bit less
+fn min_run_length(mut len: usize) -> usize {
+    let mut rest = 0;
+    while len >= MIN_MERGE {
+        rest |= len & 1;
+        len >>= 1;
+    }
+    len + rest
+}
+
+// Returns the end of the run starting at lo, strictly descending runs are reversed in place
+fn count_run_and_make_ascending<T>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    lo: usize,
+) -> usize {
+    let len = arr.len();
+    let mut hi = lo + 1;
+    if hi == len {
+        return hi;
+    }
+
+    steps.push_front(SortType::Compare(hi, lo));
+    if cmp(&arr[hi], &arr[lo]).is_lt() {
+        hi += 1;
+        while hi < len {
+            steps.push_front(SortType::Compare(hi, hi - 1));
+            if cmp(&arr[hi], &arr[hi - 1]).is_ge() {
+                break;
+            }
+            hi += 1;
+        }
+        let (mut left, mut right) = (lo, hi - 1);
+        while left < right {
+            arr.swap(left, right);
+            steps.push_front(SortType::Swap(left, right));
+            left += 1;
+            right -= 1;
+        }
+    } else {
+        hi += 1;
+        while hi < len {
+            steps.push_front(SortType::Compare(hi, hi - 1));
+            if cmp(&arr[hi], &arr[hi - 1]).is_lt() {
+                break;
+            }
+            hi += 1;
+        }
+    }
+    hi
+}
+
+// arr[lo..start] is already sorted, inserts the rest of arr[lo..hi] into it one by one
+fn binary_insertion_sort<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    lo: usize,
+    start: usize,
+    hi: usize,
+) {
+    for i in start..hi {
+        let pivot = arr[i];
+        let (mut left, mut right) = (lo, i);
+        while left < right {
+            let mid = left + (right - left) / 2;
+            steps.push_front(SortType::Compare(i, mid));
+            if cmp(&pivot, &arr[mid]).is_lt() {
+                right = mid;
+            } else {
+                left = mid + 1;
+            }
+        }
+
+        for j in (left..i).rev() {
+            arr[j + 1] = arr[j];
+            steps.push_front(SortType::Set(j + 1, arr[j]));
+        }
+        if left != i {
+            arr[left] = pivot;
+            steps.push_front(SortType::Set(left, pivot));
+        }
+    }
+}
+
+// Keeps run lengths growing at least like Fibonacci numbers from the top of the stack down
+fn merge_collapse<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    runs: &mut Vec<(usize, usize)>,
+    min_gallop: &mut usize,
+) {
+    let run_len = |runs: &Vec<(usize, usize)>, idx: usize| runs[idx].1 - runs[idx].0;
+    while runs.len() > 1 {
+        let n = runs.len();
+        if (n >= 3 && run_len(runs, n - 3) <= run_len(runs, n - 2) + run_len(runs, n - 1))
+            || (n >= 4 && run_len(runs, n - 4) <= run_len(runs, n - 3) + run_len(runs, n - 2))
+        {
+            if run_len(runs, n - 3) < run_len(runs, n - 1) {
+                merge_at(arr, steps, cmp, runs, n - 3, min_gallop);
+            } else {
+                merge_at(arr, steps, cmp, runs, n - 2, min_gallop);
+            }
+        } else if run_len(runs, n - 2) <= run_len(runs, n - 1) {
+            merge_at(arr, steps, cmp, runs, n - 2, min_gallop);
+        } else {
+            break;
+        }
+    }
+}
+
+fn merge_at<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    runs: &mut Vec<(usize, usize)>,
+    idx: usize,
+    min_gallop: &mut usize,
+) {
+    let (lo, mid) = runs[idx];
+    let (_, hi) = runs.remove(idx + 1);
+    merge_runs(arr, steps, cmp, lo, mid, hi, min_gallop);
+    runs[idx] = (lo, hi);
+    steps.push_front(SortType::Run(lo, hi));
+}
+
+// Counts how many leading items satisfy `pred`, which has to hold for a prefix only.
+// Probes 1, 3, 7, ... items ahead first and then binary searches the last gap.
+fn gallop(len: usize, mut pred: impl FnMut(usize) -> bool) -> usize {
+    let mut last = 0;
+    let mut offset = 1;
+    while offset <= len && pred(offset - 1) {
+        last = offset;
+        offset = offset * 2 + 1;
+    }
+    let mut right = offset.min(len + 1) - 1;
+    let mut left = last;
+    while left < right {
+        let mid = left + (right - left) / 2;
+        if pred(mid) {
+            left = mid + 1;
+        } else {
+            right = mid;
+        }
+    }
+    left
+}
+
+// The merge reads the left run from a copy, but the screen keeps drawing its remaining items
+// in the gap between the merged prefix and the rest of the right run
+struct Gap {
+    lo: usize,
+    // Where each item of the left run is drawn
+    shown: Vec<usize>,
+    // Which item of the left run is drawn at lo + offset
+    held: Vec<usize>,
+}
+
+impl Gap {
+    fn new(lo: usize, mid: usize, hi: usize) -> Self {
+        Gap { lo, shown: (lo..mid).collect(), held: (0..hi - lo).collect() }
+    }
+
+    // Right item j goes to k, the left item drawn at k moves to its place
+    fn take_right<T>(&mut self, steps: &mut VecDeque<SortType<T>>, k: usize, j: usize) {
+        steps.push_front(SortType::Swap(k, j));
+        let item = self.held[k - self.lo];
+        self.held[j - self.lo] = item;
+        self.shown[item] = j;
+    }
+
+    // Left item i goes to k, the left item drawn at k moves to its place
+    fn take_left<T>(&mut self, steps: &mut VecDeque<SortType<T>>, k: usize, i: usize) {
+        let from = self.shown[i];
+        if from != k {
+            steps.push_front(SortType::Swap(k, from));
+            let item = self.held[k - self.lo];
+            self.held[from - self.lo] = item;
+            self.shown[item] = from;
+        }
+    }
+}
+
+// Merges the adjacent sorted runs arr[lo..mid] and arr[mid..hi] through a copy of the left one.
+// Once one side keeps winning, galloping copies whole blocks of it at once.
+fn merge_runs<T: Copy>(
+    arr: &mut [T],
+    steps: &mut VecDeque<SortType<T>>,
+    cmp: &Comparator<T>,
+    lo: usize,
+    mid: usize,
+    hi: usize,
+    min_gallop: &mut usize,
+) {
+    let left = arr[lo..mid].to_vec();
+    let mut gap = Gap::new(lo, mid, hi);
+    steps.push_front(SortType::Line(6));
+    steps.push_front(SortType::Alloc(left.len()));
+
+    let (mut i, mut j, mut k) = (0, mid, lo);
+    let (mut left_wins, mut right_wins) = (0, 0);
+    while i < left.len() && j < hi {
+        steps.push_front(SortType::Line(7));
+        steps.push_front(SortType::Compare(gap.shown[i], j));
+        if cmp(&arr[j], &left[i]).is_lt() {
+            arr[k] = arr[j];
+            gap.take_right(steps, k, j);
+            j += 1;
+            right_wins += 1;
+            left_wins = 0;
+        } else {
+            arr[k] = left[i];
+            gap.take_left(steps, k, i);
+            i += 1;
+            left_wins += 1;
+            right_wins = 0;
+        }
+        k += 1;
+
+        if (left_wins >= *min_gallop || right_wins >= *min_gallop) && i < left.len() && j < hi {
+            steps.push_front(SortType::Line(8));
+            let count = if left_wins > 0 {
+                // Left items not greater than arr[j] keep their place in front of it
+                let key = arr[j];
+                let count = gallop(left.len() - i, |offset| {
+                    steps.push_front(SortType::Compare(gap.shown[i + offset], j));
+                    cmp(&left[i + offset], &key).is_le()
+                });
+                for _ in 0..count {
+                    arr[k] = left[i];
+                    gap.take_left(steps, k, i);
+                    k += 1;
+                    i += 1;
+                }
+                count
+            } else {
+                // Right items smaller than left[i] go before it
+                let key = left[i];
+                let count = gallop(hi - j, |offset| {
+                    steps.push_front(SortType::Compare(gap.shown[i], j + offset));
+                    cmp(&arr[j + offset], &key).is_lt()
+                });
+                for _ in 0..count {
+                    arr[k] = arr[j];
+                    gap.take_right(steps, k, j);
+                    k += 1;
+                    j += 1;
+                }
+                count
+            };
+            // Galloping paid off, so enter it sooner next time, otherwise later
+            if count >= MIN_GALLOP {
+                *min_gallop = min_gallop.saturating_sub(1).max(1);
+            } else {
+                *min_gallop += 1;
+            }
+            left_wins = 0;
+            right_wins = 0;
+        }
+    }
+
+    // What is left of the right run is already in place
+    steps.push_front(SortType::Line(9));
+    while i < left.len() {
+        arr[k] = left[i];
+        gap.take_left(steps, k, i);
+        k += 1;
+        i += 1;
+    }
+    steps.push_front(SortType::Free(left.len()));
+}